@@ -0,0 +1,55 @@
+//! Amortized multi-bundle proof + signature verification
+//!
+//! [`OrchardBundle::verify_proof`](crate::OrchardBundle::verify_proof) checks
+//! one bundle at a time, paying the full Halo2 verification cost per call.
+//! For block/mempool validation, [`BatchValidator`] queues many bundles and
+//! runs one batched proof verification and one batched signature
+//! verification over all of them at once, amortizing the expensive MSM/
+//! pairing work across the whole set.
+
+use crate::OrchardBundle;
+
+/// Queues [`OrchardBundle`]s for one batched verification pass
+///
+/// Mirrors the queue-then-flush shape of the underlying
+/// [`orchard::bundle::BatchValidator`], but works directly against our own
+/// [`OrchardBundle`] wrapper. Empty/absent bundles are skipped when queued -
+/// they have no proof or signatures to contribute, and so can never fail
+/// the batch.
+pub struct BatchValidator {
+    inner: orchard::bundle::BatchValidator,
+}
+
+impl BatchValidator {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self {
+            inner: orchard::bundle::BatchValidator::new(),
+        }
+    }
+
+    /// Queue `bundle`'s Halo2 proof and RedPallas binding/spend-auth
+    /// signatures for batched verification against `sighash`
+    ///
+    /// No-op if `bundle` is empty/absent.
+    pub fn queue_bundle(&mut self, bundle: &OrchardBundle, sighash: [u8; 32]) {
+        if let Some(inner) = bundle.inner() {
+            self.inner.add_bundle(inner, sighash);
+        }
+    }
+
+    /// Run one batched proof verification and one batched signature
+    /// verification over every bundle queued so far
+    ///
+    /// Returns `true` only if every queued bundle's proof and every queued
+    /// signature verify.
+    pub fn validate(self, vk: &orchard::circuit::VerifyingKey) -> bool {
+        self.inner.validate(vk, rand::rngs::OsRng)
+    }
+}
+
+impl Default for BatchValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,114 +3,241 @@
 //! This tool generates valid Orchard bundles for testing purposes.
 //! It outputs the serialized bundle as hex, which can be used in C++ tests.
 
-use orchard::{
-    builder::Builder,
-    bundle::Flags,
-    keys::{FullViewingKey, Scope, SpendingKey},
-    tree::MerkleHashOrchard,
-    value::NoteValue,
-    Address, Anchor,
+use orchard::keys::{FullViewingKey, Scope, SpendingKey};
+use orchard_postfiat::bundle_builder::{
+    build_shielded_to_shielded_from_wallet_with_rng, build_shielded_to_transparent_with_rng,
+    build_transparent_to_shielded_with_rng,
+    generate_test_spending_key, get_address_from_sk, get_empty_anchor,
 };
-use rand::rngs::OsRng;
+use orchard_postfiat::wallet_state::OrchardWalletState;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use std::env;
+use std::io::Cursor;
+use zcash_primitives::transaction::components::orchard as orchard_serialization;
+
+/// Flow shape to generate, picked by the `kind` CLI argument
+enum Kind {
+    /// Transparent -> shielded: one output funded straight from the
+    /// transparent pool, no spends
+    TransparentToShielded,
+    /// Shielded -> shielded: spends a note created by an initial t->z
+    /// bundle and sends (part of) it to a second address
+    ShieldedToShielded,
+    /// Shielded -> transparent: spends a note created by an initial t->z
+    /// bundle and unshields (part of) it back to the transparent pool
+    ShieldedToTransparent,
+}
 
-/// Generate a deterministic spending key for testing
-fn generate_test_spending_key(seed_byte: u8) -> SpendingKey {
-    let mut seed = [0u8; 32];
-    seed[0] = seed_byte;
-    SpendingKey::from_bytes(seed).expect("Valid seed for test key")
+impl Kind {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "t2z" => Ok(Kind::TransparentToShielded),
+            "z2z" => Ok(Kind::ShieldedToShielded),
+            "z2t" => Ok(Kind::ShieldedToTransparent),
+            other => Err(format!("Unknown kind '{other}', expected t2z, z2z or z2t")),
+        }
+    }
 }
 
-/// Generate a recipient address from a spending key
-fn get_address_from_sk(sk: &SpendingKey, index: u32) -> Address {
+/// Fund a fresh wallet with one note via a t->z bundle, so z2z/z2t bundles
+/// have a real spendable note (and matching Merkle witness) to build from
+///
+/// The funding bundle itself is never emitted, but the note it creates (and
+/// so the anchor and nullifier of the bundle that spends it) ends up in the
+/// printed bundle, so it draws from the same `rng` as the printed bundle.
+fn fund_wallet<R: RngCore + CryptoRng>(
+    sk: &SpendingKey,
+    funding_amount: u64,
+    rng: &mut R,
+) -> Result<OrchardWalletState, String> {
     let fvk = FullViewingKey::from(sk);
-    fvk.address_at(index, Scope::External)
-}
+    let funding_addr = fvk.address_at(0u32, Scope::External);
+    let anchor = get_empty_anchor();
+
+    // The funding bundle is never emitted or included in a real transaction,
+    // so there's no enclosing sighash to sign against.
+    let bundle_bytes = build_transparent_to_shielded_with_rng(funding_amount, funding_addr, anchor, [0u8; 32], rng)?;
+    let mut reader = Cursor::new(bundle_bytes.as_slice());
+    let inner = orchard_serialization::read_v5_bundle(&mut reader)
+        .map_err(|e| format!("Failed to parse funding bundle: {:?}", e))?
+        .ok_or_else(|| "Funding bundle has no inner Orchard bundle".to_string())?;
+
+    let mut wallet = OrchardWalletState::new();
+    wallet.add_fvk(&fvk);
+    for action in inner.actions() {
+        wallet.append_commitment(action.cmx().to_bytes())?;
+    }
+    wallet.try_decrypt_notes_from_bundle(&inner, [0u8; 32], 1)?;
 
-/// Get the empty anchor
-fn get_empty_anchor() -> Anchor {
-    Anchor::from(MerkleHashOrchard::empty_root(32.into()))
+    Ok(wallet)
 }
 
-/// Create a transparent-to-shielded bundle
-fn build_transparent_to_shielded(
+/// Small JSON record describing the parameters that produced a bundle, so a
+/// checked-in fixture carries everything needed to regenerate it
+struct BundleRecord {
+    kind: String,
     amount_drops: u64,
-    recipient: Address,
-    anchor: Anchor,
-) -> Result<Vec<u8>, String> {
-    let flags = Flags::from_parts(true, true);
-    let mut builder = Builder::new(flags, anchor);
-
-    builder
-        .add_output(None, recipient, NoteValue::from_raw(amount_drops), None)
-        .map_err(|e| format!("Failed to add output: {:?}", e))?;
-
-    let mut rng = OsRng;
-    let unproven = builder
-        .build(&mut rng)
-        .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
-
-    match unproven {
-        Some(unproven_bundle) => {
-            let pk = orchard::circuit::ProvingKey::build();
-            let proven = unproven_bundle
-                .create_proof(&pk, &mut rng)
-                .map_err(|e| format!("Failed to create proof: {:?}", e))?;
-
-            let dummy_sighash = [0u8; 32];
-            let authorized = proven
-                .apply_signatures(&mut rng, dummy_sighash, &[])
-                .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
-
-            let mut bundle_bytes = Vec::new();
-            zcash_primitives::transaction::components::orchard::write_v5_bundle(
-                Some(&authorized),
-                &mut bundle_bytes,
-            )
-            .map_err(|e| format!("Failed to serialize bundle: {:?}", e))?;
-
-            Ok(bundle_bytes)
+    recipient_seed: u8,
+    seed: u64,
+    anchor: [u8; 32],
+    value_balance: i64,
+    nullifiers: Vec<[u8; 32]>,
+    bundle_hex: String,
+}
+
+impl BundleRecord {
+    fn from_bundle(kind: &str, amount_drops: u64, recipient_seed: u8, seed: u64, bundle_bytes: &[u8]) -> Self {
+        let bundle = orchard_postfiat::OrchardBundle::parse(bundle_bytes)
+            .expect("freshly generated bundle always parses");
+
+        Self {
+            kind: kind.to_string(),
+            amount_drops,
+            recipient_seed,
+            seed,
+            anchor: bundle.anchor(),
+            value_balance: bundle.value_balance(),
+            nullifiers: bundle.nullifiers(),
+            bundle_hex: hex::encode(bundle_bytes),
         }
-        None => Err("Builder produced empty bundle".to_string()),
+    }
+
+    fn to_json(&self) -> String {
+        let nullifiers_json = self
+            .nullifiers
+            .iter()
+            .map(|n| format!("\"{}\"", hex::encode(n)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{{\"kind\":\"{}\",\"amount_drops\":{},\"recipient_seed\":{},\"seed\":{},\"anchor\":\"{}\",\"value_balance\":{},\"nullifiers\":[{}],\"bundle_hex\":\"{}\"}}",
+            self.kind,
+            self.amount_drops,
+            self.recipient_seed,
+            self.seed,
+            hex::encode(self.anchor),
+            self.value_balance,
+            nullifiers_json,
+            self.bundle_hex,
+        )
     }
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // Pull `--seed <u64>` and `--json` out of the argument list wherever
+    // they appear, leaving only the positional <kind> <amount> [recipient_seed]
+    let mut seed: Option<u64> = None;
+    let mut json_output = false;
+    let mut positional = vec![args.remove(0)];
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--seed" => {
+                let value = args.get(i + 1).unwrap_or_else(|| {
+                    eprintln!("Error: --seed requires a value");
+                    std::process::exit(1);
+                });
+                seed = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("Error: --seed value must be a u64");
+                    std::process::exit(1);
+                }));
+                i += 2;
+            }
+            "--json" => {
+                json_output = true;
+                i += 1;
+            }
+            other => {
+                positional.push(other.to_string());
+                i += 1;
+            }
+        }
+    }
+    let args = positional;
 
-    if args.len() < 2 {
-        eprintln!("Usage: {} <amount_in_drops> [recipient_seed]", args[0]);
+    if args.len() < 3 {
+        eprintln!("Usage: {} <kind> <amount_in_drops> [recipient_seed] [--seed <u64>] [--json]", args[0]);
+        eprintln!();
+        eprintln!("  <kind> is one of: t2z, z2z, z2t");
+        eprintln!("  --seed sets the ChaChaRng seed for bundle construction (default 0),");
+        eprintln!("         so the same arguments always produce the same bundle bytes");
+        eprintln!("  --json prints a JSON record (amount, recipient seed, anchor, value");
+        eprintln!("         balance, nullifiers, bundle hex) instead of bare hex, so a");
+        eprintln!("         checked-in fixture carries its generating parameters");
         eprintln!();
         eprintln!("Examples:");
-        eprintln!("  {} 1000000000  # Generate bundle for 1000 XRP (1 billion drops)", args[0]);
-        eprintln!("  {} 100 42      # Generate bundle for 100 drops to recipient from seed 42", args[0]);
+        eprintln!("  {} t2z 1000000000                  # Fund a shielded address with 1000 XRP", args[0]);
+        eprintln!("  {} z2z 100 42 --seed 7 --json       # Reproducible z2z fixture with metadata", args[0]);
+        eprintln!("  {} z2t 100                          # Spend a note and unshield 100 drops", args[0]);
         std::process::exit(1);
     }
 
-    let amount: u64 = args[1].parse().expect("Amount must be a valid number");
+    let kind_str = &args[1];
+    let kind = Kind::parse(kind_str).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+    let amount: u64 = args[2].parse().expect("Amount must be a valid number");
     let recipient_seed: u8 = args
-        .get(2)
+        .get(3)
         .map(|s| s.parse().expect("Recipient seed must be 0-255"))
         .unwrap_or(42);
 
     eprintln!("Generating Orchard bundle...");
     eprintln!("  Amount: {} drops", amount);
     eprintln!("  Recipient seed: {}", recipient_seed);
+    if let Some(seed) = seed {
+        eprintln!("  RNG seed: {} (deterministic)", seed);
+    }
     eprintln!();
     eprintln!("This will take ~5-10 seconds for proof generation...");
 
-    let recipient_sk = generate_test_spending_key(recipient_seed);
-    let recipient_addr = get_address_from_sk(&recipient_sk, 0);
-    let anchor = get_empty_anchor();
+    let sk = generate_test_spending_key(recipient_seed);
+
+    // Default to a fixed seed of 0 when none is given, so bundle bytes are
+    // still reproducible by default; pass `--seed` explicitly to vary them.
+    let mut rng = ChaCha20Rng::seed_from_u64(seed.unwrap_or(0));
 
-    match build_transparent_to_shielded(amount, recipient_addr, anchor) {
+    // This tool emits a bare bundle with no enclosing transaction, so there's
+    // no real ZIP-244 sighash to sign against; a fixed placeholder keeps
+    // output reproducible like the rest of this tool's fields.
+    let sighash = [0u8; 32];
+
+    let result = match kind {
+        Kind::TransparentToShielded => {
+            let recipient_addr = get_address_from_sk(&sk, 0);
+            let anchor = get_empty_anchor();
+            build_transparent_to_shielded_with_rng(amount, recipient_addr, anchor, sighash, &mut rng)
+        }
+        Kind::ShieldedToShielded => fund_wallet(&sk, amount.saturating_mul(2), &mut rng).and_then(|wallet| {
+            let other_recipient = get_address_from_sk(&generate_test_spending_key(recipient_seed.wrapping_add(1)), 0);
+            build_shielded_to_shielded_from_wallet_with_rng(&wallet, &sk.to_bytes(), other_recipient, amount, 0, None, sighash, &mut rng)
+        }),
+        Kind::ShieldedToTransparent => {
+            fund_wallet(&sk, amount.saturating_mul(2), &mut rng).and_then(|wallet| {
+                build_shielded_to_transparent_with_rng(&wallet, &sk.to_bytes(), amount, sighash, &mut rng)
+            })
+        }
+    };
+
+    match result {
         Ok(bundle_bytes) => {
             eprintln!();
             eprintln!("Bundle generated successfully!");
             eprintln!("  Size: {} bytes", bundle_bytes.len());
             eprintln!();
-            eprintln!("Hex-encoded bundle:");
-            println!("{}", hex::encode(&bundle_bytes));
+
+            if json_output {
+                let record = BundleRecord::from_bundle(kind_str, amount, recipient_seed, seed.unwrap_or(0), &bundle_bytes);
+                println!("{}", record.to_json());
+            } else {
+                eprintln!("Hex-encoded bundle:");
+                println!("{}", hex::encode(&bundle_bytes));
+            }
         }
         Err(e) => {
             eprintln!("Error generating bundle: {}", e);
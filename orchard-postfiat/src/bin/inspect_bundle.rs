@@ -0,0 +1,32 @@
+//! Inspect a serialized v5 Orchard bundle
+//!
+//! Mirrors a transaction inspector: given raw `write_v5_bundle` bytes (e.g.
+//! straight out of `generate_test_bundle`), prints a structured summary
+//! without wiring up a full transaction validation flow.
+
+use orchard_postfiat::OrchardBundle;
+use std::env;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 2 {
+        eprintln!("Usage: {} <hex-encoded bundle bytes>", args[0]);
+        eprintln!();
+        eprintln!("Example:");
+        eprintln!("  {} $(cargo run --bin generate_test_bundle -- t2z 1000000000)", args[0]);
+        std::process::exit(1);
+    }
+
+    let bundle_bytes = hex::decode(&args[1]).unwrap_or_else(|e| {
+        eprintln!("Error: argument is not valid hex: {e}");
+        std::process::exit(1);
+    });
+
+    let bundle = OrchardBundle::parse(&bundle_bytes).unwrap_or_else(|e| {
+        eprintln!("Error: failed to parse bundle: {e}");
+        std::process::exit(1);
+    });
+
+    print!("{}", bundle.inspect());
+}
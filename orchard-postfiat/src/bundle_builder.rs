@@ -15,9 +15,63 @@ use orchard::{
 };
 use incrementalmerkletree::Hashable;
 use rand::rngs::OsRng;
-use zcash_note_encryption::try_note_decryption;
+use rand::{CryptoRng, RngCore};
+use zcash_note_encryption::{try_note_decryption, try_output_recovery_with_ovk};
 use zcash_protocol::value::ZatBalance;
 
+/// The Halo2 proving key used to create Orchard proofs
+///
+/// Building this is expensive (it's the same cost every `build_*` function
+/// used to pay on every single call), so it's built once and shared across
+/// every proof generation - single-bundle or batched - for the life of the
+/// process. Mirrors [`crate::bundle_real::orchard_verifying_key`].
+pub(crate) fn orchard_proving_key() -> &'static orchard::circuit::ProvingKey {
+    static PROVING_KEY: std::sync::OnceLock<orchard::circuit::ProvingKey> = std::sync::OnceLock::new();
+    PROVING_KEY.get_or_init(orchard::circuit::ProvingKey::build)
+}
+
+/// One bundle that has been built but not yet proven
+type UnprovenBundle<V> = Bundle<orchard::builder::InProgress<orchard::builder::Unproven, orchard::builder::Unauthorized>, V>;
+
+/// The same bundle once [`create_proofs_parallel`] has proven it
+type ProvenBundle<V> = Bundle<orchard::builder::InProgress<orchard::circuit::Proof, orchard::builder::Unauthorized>, V>;
+
+/// Prove a batch of already-built, still-unproven bundles in parallel
+///
+/// `create_proof` is CPU-bound and, once every bundle has been built, fully
+/// independent of every other bundle - so instead of proving a block's
+/// shielded transactions one at a time at ~5-10s each, this fans them out
+/// one thread per bundle, all sharing the single process-global
+/// [`orchard_proving_key`]. Wall-clock time for a batch shrinks roughly
+/// linearly with the number of available cores instead of the bundle count.
+///
+/// Returns proven bundles in the same order as `unproven`, or the first
+/// error encountered.
+pub fn create_proofs_parallel<V: Send + 'static>(
+    unproven: Vec<UnprovenBundle<V>>,
+) -> Result<Vec<ProvenBundle<V>>, String> {
+    let pk = orchard_proving_key();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = unproven
+            .into_iter()
+            .map(|bundle| {
+                scope.spawn(move || {
+                    let mut rng = OsRng;
+                    bundle
+                        .create_proof(pk, &mut rng)
+                        .map_err(|e| format!("Failed to create proof: {:?}", e))
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("proving thread panicked"))
+            .collect()
+    })
+}
+
 /// Generate a cryptographically secure random spending key
 ///
 /// Uses the operating system's random number generator (OsRng) to generate
@@ -84,31 +138,170 @@ pub fn try_decrypt_note(
     }
 }
 
-/// Try to decrypt a note from raw encrypted ciphertext
+/// Trial-decrypt many actions across many bundles against many incoming
+/// viewing keys in a single batched pass
+///
+/// Looping [`try_decrypt_note`] over every (action, key) pair repeats the
+/// expensive per-action Diffie-Hellman key agreement once per key tried.
+/// This instead flattens every action across `bundles` into one list and
+/// runs [`zcash_note_encryption::batch::try_note_decryption`] over the whole
+/// set against every key in `ivks` at once, amortizing that group
+/// arithmetic across the batch - the throughput-critical path for catching
+/// a wallet up over a long history. See
+/// [`crate::wallet_state::OrchardWalletState::batch_decrypt_notes`] for the
+/// same approach wired into wallet note-tracking.
+///
+/// Returns `(bundle_index, action_index, ivk_index, value_drops, recipient, memo)`
+/// for every action that decrypted against any key, in bundle/action order.
+pub fn try_decrypt_notes_batch(
+    bundles: &[Bundle<orchard::bundle::Authorized, ZatBalance>],
+    ivks: &[PreparedIncomingViewingKey],
+) -> Vec<(usize, usize, usize, u64, Address, [u8; 512])> {
+    let mut domains_and_actions = Vec::new();
+    let mut origins = Vec::new();
+    for (bundle_idx, bundle) in bundles.iter().enumerate() {
+        for (action_idx, action) in bundle.actions().iter().enumerate() {
+            let domain = orchard::note_encryption::OrchardDomain::for_action(action);
+            domains_and_actions.push((domain, action.clone()));
+            origins.push((bundle_idx, action_idx));
+        }
+    }
+
+    if domains_and_actions.is_empty() {
+        return Vec::new();
+    }
+
+    let results = zcash_note_encryption::batch::try_note_decryption(ivks, &domains_and_actions);
+
+    results
+        .into_iter()
+        .zip(origins.into_iter())
+        .filter_map(|(result, (bundle_idx, action_idx))| {
+            let (note, recipient, memo, ivk_index) = result?;
+            Some((bundle_idx, action_idx, ivk_index, note.value().inner(), recipient, memo))
+        })
+        .collect()
+}
+
+/// Try to recover a note a sender created, from their own `FullViewingKey`
+///
+/// Mirrors [`try_decrypt_note`], but walks the outgoing viewing key path
+/// (`try_output_recovery_with_ovk`) instead of the incoming one, so a sender
+/// can reconstruct the value, recipient address and memo of an output they
+/// created themselves - `try_decrypt_note` can't do this for a sender's own
+/// outputs, since its incoming viewing key only unlocks notes sent *to* it.
+///
+/// Returns `None` if `fvk`'s outgoing viewing key doesn't match the output at
+/// `action_index`.
+pub fn try_decrypt_outgoing(
+    bundle: &Bundle<orchard::bundle::Authorized, ZatBalance>,
+    action_index: usize,
+    fvk: &FullViewingKey,
+) -> Option<(u64, Address, [u8; 512])> {
+    let action = bundle.actions().get(action_index)?;
+
+    let ovk = fvk.to_ovk(Scope::External);
+    let domain = orchard::note_encryption::OrchardDomain::for_action(action);
+
+    let (note, addr, memo) = try_output_recovery_with_ovk(
+        &domain,
+        &ovk,
+        action,
+        action.cv_net(),
+        &action.encrypted_note().out_ciphertext,
+    )?;
+
+    Some((note.value().inner(), addr, memo))
+}
+
+/// Recover every output a sender created in a bundle, given the
+/// [`orchard::keys::OutgoingViewingKey`] used to encrypt them
+///
+/// Walks every action in `bundle` and attempts out-ciphertext decryption
+/// against `ovk` (the same path [`try_decrypt_outgoing`] uses for a single
+/// action, keyed off a caller's own `FullViewingKey`), returning the
+/// recovered `(recipient address, value in drops, memo)` for each action
+/// that decrypts. Actions that don't belong to `ovk` are skipped.
+pub fn recover_outputs_with_ovk(
+    bundle: &crate::OrchardBundle,
+    ovk: &orchard::keys::OutgoingViewingKey,
+) -> Vec<(Address, u64, [u8; 512])> {
+    let Some(inner) = bundle.inner() else {
+        return Vec::new();
+    };
+
+    inner
+        .actions()
+        .iter()
+        .filter_map(|action| {
+            let domain = orchard::note_encryption::OrchardDomain::for_action(action);
+            let (note, addr, memo) = try_output_recovery_with_ovk(
+                &domain,
+                ovk,
+                action,
+                action.cv_net(),
+                &action.encrypted_note().out_ciphertext,
+            )?;
+            Some((addr, note.value().inner(), memo))
+        })
+        .collect()
+}
+
+/// Try to decrypt a note from the compact per-action fields stored in
+/// ledger state, without needing the full Orchard bundle
 ///
-/// This is used to decrypt notes retrieved from ledger state.
+/// Reconstructs an [`orchard::note_encryption::CompactAction`] from exactly
+/// the fields a compact-block-style scanner persists per action - the spent
+/// note's nullifier, the output's extracted note commitment (`cmx`), its
+/// ephemeral key, and the leading `COMPACT_NOTE_SIZE` bytes of the output
+/// ciphertext (the rest of the 580-byte ciphertext only carries the memo,
+/// which compact decryption doesn't recover). This is what lets the ledger
+/// avoid retaining the full bundle per transaction just so a wallet can scan
+/// for its own notes later.
 ///
-/// NOTE: Due to Orchard library limitations, this approach won't work with just the encrypted ciphertext.
-/// For now, we'll need to keep the full bundle data or use a different approach.
+/// # Arguments
+/// * `encrypted_note` - The encrypted output ciphertext; only the leading
+///   `COMPACT_NOTE_SIZE` (52) bytes are used
+/// * `nullifier_bytes` - The 32-byte nullifier of the note spent by this action
+/// * `cmx_bytes` - The 32-byte extracted note commitment of this action's output
+/// * `ephemeral_key_bytes` - The 32-byte ephemeral public key
+/// * `fvk` - Full viewing key to attempt decryption with
 ///
-/// Returns None for now - we'll decrypt from the in-memory bundle instead
+/// Returns the note value in drops if decryption succeeds, None otherwise
 pub fn try_decrypt_note_from_ciphertext(
-    _encrypted_note: &[u8],
-    _cmx_bytes: &[u8; 32],
-    _ephemeral_key_bytes: &[u8; 32],
-    _fvk: &FullViewingKey,
+    encrypted_note: &[u8],
+    nullifier_bytes: &[u8; 32],
+    cmx_bytes: &[u8; 32],
+    ephemeral_key_bytes: &[u8; 32],
+    fvk: &FullViewingKey,
 ) -> Option<u64> {
-    // TODO: Orchard's CompactAction expects 52-byte compact ciphertext, not 580-byte full ciphertext
-    // We would need to either:
-    // 1. Store the full OrchardBundle in each transaction
-    // 2. Reconstruct the Action from the stored data
-    // 3. Use a different decryption method
-    //
-    // For now, return None - we'll use the in-memory bundle for decryption
-    None
+    use orchard::note::{ExtractedNoteCommitment, Nullifier};
+    use orchard::note_encryption::{CompactAction, OrchardDomain};
+    use zcash_note_encryption::{try_compact_note_decryption, EphemeralKeyBytes, COMPACT_NOTE_SIZE};
+
+    if encrypted_note.len() < COMPACT_NOTE_SIZE {
+        return None;
+    }
+    let mut compact_ciphertext = [0u8; COMPACT_NOTE_SIZE];
+    compact_ciphertext.copy_from_slice(&encrypted_note[..COMPACT_NOTE_SIZE]);
+
+    let nullifier = Nullifier::from_bytes(nullifier_bytes).into_option()?;
+    let cmx = ExtractedNoteCommitment::from_bytes(cmx_bytes).into_option()?;
+    let ephemeral_key = EphemeralKeyBytes(*ephemeral_key_bytes);
+
+    let compact_action =
+        CompactAction::from_parts(nullifier, cmx, ephemeral_key, compact_ciphertext);
+    let domain = OrchardDomain::for_compact_action(&compact_action);
+    let ivk = PreparedIncomingViewingKey::new(&fvk.to_ivk(Scope::External));
+
+    let (note, _recipient) = try_compact_note_decryption(&domain, &ivk, &compact_action)?;
+    Some(note.value().inner())
 }
 
-/// Create a transparent-to-shielded (t→z) Orchard bundle
+/// Create a transparent-to-shielded (t→z) Orchard bundle using
+/// [`BundleType::Coinbase`] - see [`build_transparent_to_shielded_typed`] to
+/// choose a different padding/action-count policy (e.g. a `Transactional`
+/// type to mix in dummy spends for privacy).
 ///
 /// This creates a bundle that:
 /// - Takes `amount` from the transparent pool (negative value_balance)
@@ -129,12 +322,62 @@ pub fn build_transparent_to_shielded(
     amount_drops: u64,
     recipient: Address,
     anchor: Anchor,
+    sighash: [u8; 32],
 ) -> Result<Vec<u8>, String> {
-    // Create builder - Coinbase allows simpler construction for t→z
-    let mut builder = Builder::new(
-        BundleType::Coinbase,
-        anchor,
-    );
+    build_transparent_to_shielded_with_rng(amount_drops, recipient, anchor, sighash, &mut OsRng)
+}
+
+/// Same as [`build_transparent_to_shielded`], but draws `build`/`create_proof`/
+/// `apply_signatures` randomness from the caller-supplied `rng` instead of
+/// always reaching for [`OsRng`] - letting a caller seed a deterministic RNG
+/// (e.g. `ChaChaRng::seed_from_u64`) to reproduce the exact same bundle bytes
+/// across runs, which `OsRng` can never do.
+pub fn build_transparent_to_shielded_with_rng<R: RngCore + CryptoRng>(
+    amount_drops: u64,
+    recipient: Address,
+    anchor: Anchor,
+    sighash: [u8; 32],
+    rng: &mut R,
+) -> Result<Vec<u8>, String> {
+    build_transparent_to_shielded_typed_with_rng(amount_drops, recipient, anchor, BundleType::Coinbase, sighash, rng)
+}
+
+/// Same as [`build_transparent_to_shielded`], but lets the caller pick the
+/// [`BundleType`] instead of always using [`BundleType::Coinbase`] - e.g. a
+/// `Transactional { flags, bundle_required }` type to produce a
+/// privacy-preserving bundle with a stable action count (Orchard's own
+/// builder pads with dummy spends/outputs up to whatever `bundle_type`
+/// requires, so callers never need to construct dummy actions themselves).
+pub fn build_transparent_to_shielded_typed(
+    amount_drops: u64,
+    recipient: Address,
+    anchor: Anchor,
+    bundle_type: BundleType,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    build_transparent_to_shielded_typed_with_rng(amount_drops, recipient, anchor, bundle_type, sighash, &mut OsRng)
+}
+
+/// Same as [`build_transparent_to_shielded_typed`], but draws `build`/
+/// `create_proof`/`apply_signatures` randomness from the caller-supplied
+/// `rng` instead of always reaching for [`OsRng`].
+pub fn build_transparent_to_shielded_typed_with_rng<R: RngCore + CryptoRng>(
+    amount_drops: u64,
+    recipient: Address,
+    anchor: Anchor,
+    bundle_type: BundleType,
+    sighash: [u8; 32],
+    rng: &mut R,
+) -> Result<Vec<u8>, String> {
+    let outputs_enabled = match bundle_type {
+        BundleType::Transactional { flags, .. } => flags.outputs_enabled(),
+        BundleType::Coinbase => true,
+    };
+    if !outputs_enabled {
+        return Err("Chosen bundle type has outputs disabled".to_string());
+    }
+
+    let mut builder = Builder::new(bundle_type, anchor);
 
     // Add output (creating a new shielded note)
     // For t→z, we have no spends, only outputs
@@ -149,28 +392,24 @@ pub fn build_transparent_to_shielded(
         .map_err(|e| format!("Failed to add output: {:?}", e))?;
 
     // Build the bundle
-    let mut rng = OsRng;
-
     let unproven = builder
-        .build(&mut rng)
+        .build(&mut *rng)
         .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
 
     match unproven {
         Some((unproven_bundle, _metadata)) => {
             // Get the proving key (this is cached globally by orchard)
-            let pk = orchard::circuit::ProvingKey::build();
+            let pk = orchard_proving_key();
 
             // Create proof (EXPENSIVE - ~5-10 seconds!)
             let proven = unproven_bundle
-                .create_proof(&pk, &mut rng)
+                .create_proof(pk, &mut *rng)
                 .map_err(|e| format!("Failed to create proof: {:?}", e))?;
 
             // For t→z, there are no spends so we don't need spend authorization
-            // Just apply signatures with a dummy sighash
-            // (The real sighash will be verified when the bundle is included in a transaction)
-            let dummy_sighash = [0u8; 32];
+            // keys, but the binding signature still commits to the real sighash.
             let authorized = proven
-                .apply_signatures(&mut rng, dummy_sighash, &[])
+                .apply_signatures(&mut *rng, sighash, &[])
                 .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
 
             // Serialize the bundle
@@ -190,6 +429,365 @@ pub fn build_transparent_to_shielded(
     }
 }
 
+/// Fund several shielded recipients from the transparent pool in a single
+/// bundle, using [`BundleType::Coinbase`] - see
+/// [`build_transparent_to_shielded_multi_typed`] to choose a different
+/// padding/action-count policy.
+///
+/// Adds one output per `(recipient, amount_drops)` pair before proving, so
+/// `value_balance()` on the resulting bundle equals the negated sum of all
+/// output values, and `num_actions()` reflects the combined (padded)
+/// action set - this avoids forcing callers to build one bundle per
+/// recipient.
+///
+/// # Note
+/// This function is EXPENSIVE - takes ~5-10 seconds due to proof generation!
+pub fn build_transparent_to_shielded_multi(
+    outputs: &[(Address, u64)],
+    anchor: Anchor,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    build_transparent_to_shielded_multi_typed(outputs, anchor, BundleType::Coinbase, sighash)
+}
+
+/// Same as [`build_transparent_to_shielded_multi`], but lets the caller
+/// pick the [`BundleType`] instead of always using [`BundleType::Coinbase`].
+pub fn build_transparent_to_shielded_multi_typed(
+    outputs: &[(Address, u64)],
+    anchor: Anchor,
+    bundle_type: BundleType,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    build_transparent_to_shielded_multi_typed_with_rng(outputs, anchor, bundle_type, sighash, &mut OsRng)
+}
+
+/// Same as [`build_transparent_to_shielded_multi_typed`], but draws
+/// `build`/`create_proof`/`apply_signatures` randomness from the
+/// caller-supplied `rng` instead of always reaching for [`OsRng`].
+pub fn build_transparent_to_shielded_multi_typed_with_rng<R: RngCore + CryptoRng>(
+    outputs: &[(Address, u64)],
+    anchor: Anchor,
+    bundle_type: BundleType,
+    sighash: [u8; 32],
+    rng: &mut R,
+) -> Result<Vec<u8>, String> {
+    if outputs.is_empty() {
+        return Err("Must specify at least one output".to_string());
+    }
+
+    let outputs_enabled = match bundle_type {
+        BundleType::Transactional { flags, .. } => flags.outputs_enabled(),
+        BundleType::Coinbase => true,
+    };
+    if !outputs_enabled {
+        return Err("Chosen bundle type has outputs disabled".to_string());
+    }
+
+    let mut builder = Builder::new(bundle_type, anchor);
+
+    let memo = [0u8; 512];
+    for (recipient, amount_drops) in outputs {
+        builder
+            .add_output(None, *recipient, NoteValue::from_raw(*amount_drops), memo)
+            .map_err(|e| format!("Failed to add output: {:?}", e))?;
+    }
+
+    let unproven = builder
+        .build(&mut *rng)
+        .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
+
+    match unproven {
+        Some((unproven_bundle, _metadata)) => {
+            let pk = orchard_proving_key();
+
+            let proven = unproven_bundle
+                .create_proof(pk, &mut *rng)
+                .map_err(|e| format!("Failed to create proof: {:?}", e))?;
+
+            let authorized = proven
+                .apply_signatures(&mut *rng, sighash, &[])
+                .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
+
+            let mut bundle_bytes = Vec::new();
+            zcash_primitives::transaction::components::orchard::write_v5_bundle(
+                Some(&authorized),
+                &mut bundle_bytes,
+            )
+            .map_err(|e| format!("Failed to serialize bundle: {:?}", e))?;
+
+            Ok(bundle_bytes)
+        }
+        None => Err("Builder produced empty bundle".to_string()),
+    }
+}
+
+/// A note the caller is ready to spend, supplied directly rather than
+/// selected from an [`crate::wallet_state::OrchardWalletState`] or
+/// [`crate::note_manager::NoteManager`]
+///
+/// This mirrors the information [`Builder::add_spend`] itself needs (the
+/// note's [`FullViewingKey`], the [`orchard::Note`] and its [`MerklePath`]),
+/// plus the [`SpendAuthorizingKey`] needed to actually sign for it later -
+/// so a caller doing its own note/witness bookkeeping can build a bundle
+/// without standing up a wallet state.
+pub struct RawSpendInput {
+    pub fvk: FullViewingKey,
+    pub note: orchard::Note,
+    pub merkle_path: orchard::tree::MerklePath,
+    pub ask: SpendAuthorizingKey,
+}
+
+/// Build an Orchard bundle from caller-supplied spends and outputs
+///
+/// This is the general entry point underneath every other builder in this
+/// module: given a real tree `anchor`, a set of [`RawSpendInput`]s (each of
+/// which must have a `merkle_path` rooting to `anchor`, otherwise this
+/// returns an "anchor mismatch" error), and a list of `(recipient, value)`
+/// outputs, it adds each spend and output to the builder exactly like
+/// [`build_shielded_to_shielded_from_wallet_with_rng`] does, lets the
+/// builder balance inputs against outputs to determine `value_balance`,
+/// generates the proof and signs it, and serializes via `write_v5_bundle`.
+/// `spends` may be empty (a pure mint from the transparent pool) and
+/// `outputs` may be empty (a pure unshield), but not both.
+///
+/// The resulting bytes can be parsed with [`crate::OrchardBundle::parse`] to
+/// inspect `nullifiers()` and `value_balance()`, the same way every other
+/// builder's bundle is inspected.
+///
+/// `sighash` must be the enclosing transaction's real ZIP-244 sighash - every
+/// spend authorization signature and the binding signature are computed over
+/// it, so the bundle will only validate against that exact sighash.
+pub fn build_orchard_bundle(
+    anchor: Anchor,
+    spends: &[RawSpendInput],
+    outputs: &[(Address, u64)],
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    build_orchard_bundle_with_rng(anchor, spends, outputs, sighash, &mut OsRng)
+}
+
+/// Same as [`build_orchard_bundle`], but draws `build`/`create_proof`/
+/// `apply_signatures` randomness from the caller-supplied `rng` instead of
+/// always reaching for [`OsRng`].
+pub fn build_orchard_bundle_with_rng<R: RngCore + CryptoRng>(
+    anchor: Anchor,
+    spends: &[RawSpendInput],
+    outputs: &[(Address, u64)],
+    sighash: [u8; 32],
+    rng: &mut R,
+) -> Result<Vec<u8>, String> {
+    if spends.is_empty() && outputs.is_empty() {
+        return Err("Bundle would have no spends and no outputs".to_string());
+    }
+
+    let mut builder = Builder::new(
+        BundleType::Transactional {
+            flags: orchard::bundle::Flags::ENABLED,
+            bundle_required: true,
+        },
+        anchor,
+    );
+
+    for spend in spends {
+        let extracted_cmx = orchard::note::ExtractedNoteCommitment::from(spend.note.commitment());
+        if spend.merkle_path.root(extracted_cmx) != anchor {
+            return Err("Anchor mismatch: spend's Merkle path does not root to the bundle anchor".to_string());
+        }
+
+        builder
+            .add_spend(spend.fvk.clone(), spend.note.clone(), spend.merkle_path.clone())
+            .map_err(|e| format!("Failed to add spend: {:?}", e))?;
+    }
+
+    let memo = [0u8; 512];
+    for (recipient, value) in outputs {
+        builder
+            .add_output(None, *recipient, NoteValue::from_raw(*value), memo)
+            .map_err(|e| format!("Failed to add output: {:?}", e))?;
+    }
+
+    let unproven = builder
+        .build(&mut *rng)
+        .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
+
+    match unproven {
+        Some((unproven_bundle, _metadata)) => {
+            let pk = orchard_proving_key();
+
+            let proven = unproven_bundle
+                .create_proof(pk, &mut *rng)
+                .map_err(|e| format!("Failed to create proof: {:?}", e))?;
+
+            let saks: Vec<SpendAuthorizingKey> = spends.iter().map(|spend| spend.ask.clone()).collect();
+
+            let authorized = proven
+                .apply_signatures(&mut *rng, sighash, &saks)
+                .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
+
+            let mut bundle_bytes = Vec::new();
+            zcash_primitives::transaction::components::orchard::write_v5_bundle(
+                Some(&authorized),
+                &mut bundle_bytes,
+            )
+            .map_err(|e| format!("Failed to serialize bundle: {:?}", e))?;
+
+            Ok(bundle_bytes)
+        }
+        None => Err("Builder produced empty bundle".to_string()),
+    }
+}
+
+/// Companion to [`build_transparent_to_shielded`] for the z→z and z→t
+/// shapes: spends one or more caller-supplied notes (see [`RawSpendInput`])
+/// against a real tree `anchor` and sends to a list of `(recipient, value)`
+/// outputs. Unlike [`build_orchard_bundle`], at least one spend is required
+/// - use [`build_transparent_to_shielded`] directly for a pure mint with no
+/// spends.
+pub fn build_shielded_spend(
+    anchor: Anchor,
+    spends: &[RawSpendInput],
+    outputs: &[(Address, u64)],
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    if spends.is_empty() {
+        return Err("build_shielded_spend requires at least one spend".to_string());
+    }
+    build_orchard_bundle(anchor, spends, outputs, sighash)
+}
+
+/// A note ready to spend: the full viewing key, the note, and its Merkle
+/// path (which encodes its position in the tree)
+///
+/// Unlike [`RawSpendInput`], this doesn't carry a [`SpendAuthorizingKey`] -
+/// [`build_unauthorized_bundle`] only needs enough to add the spend to the
+/// builder and compute the value balance; the spend authorizing keys are
+/// supplied later, to [`UnauthorizedBundle::prove_and_sign`].
+pub struct OrchardSpendInfo {
+    pub fvk: FullViewingKey,
+    pub note: orchard::Note,
+    pub merkle_path: orchard::tree::MerklePath,
+}
+
+/// An Orchard bundle with its spends, outputs, flags, anchor and value
+/// balance fixed, but no Halo2 proof or RedPallas signatures yet
+///
+/// Produced by [`build_unauthorized_bundle`]; call
+/// [`prove_and_sign`](Self::prove_and_sign) to generate the proof, sign
+/// every action's spend authorization plus the binding signature, and get
+/// back a fully-authorized [`crate::OrchardBundle`]. This is the
+/// production counterpart to the "for testing" builders elsewhere in this
+/// module: it spends real notes directly (no wallet state required) and
+/// never needs a spending key in scope until the caller is ready to sign.
+pub struct UnauthorizedBundle {
+    inner: orchard::Bundle<orchard::builder::InProgress<orchard::builder::Unproven, orchard::builder::Unauthorized>, i64>,
+}
+
+impl UnauthorizedBundle {
+    /// Number of actions (spends + outputs, including any padding)
+    pub fn num_actions(&self) -> usize {
+        self.inner.actions().len()
+    }
+
+    /// `(spends_enabled, outputs_enabled)` for this bundle
+    pub fn flags(&self) -> (bool, bool) {
+        (self.inner.flags().spends_enabled(), self.inner.flags().outputs_enabled())
+    }
+
+    /// Net value balance in drops (positive: leaving the shielded pool, negative: entering it)
+    pub fn value_balance(&self) -> i64 {
+        *self.inner.value_balance()
+    }
+
+    /// The tree anchor this bundle's spends were proven against
+    pub fn anchor(&self) -> Anchor {
+        *self.inner.anchor()
+    }
+
+    /// Generate the Halo2 proof, sign every action's spend authorization
+    /// with the matching key in `spend_auth_keys` plus the binding
+    /// signature, and serialize the result into a fully-authorized
+    /// [`crate::OrchardBundle`]
+    ///
+    /// `spend_auth_keys` must have exactly one entry per spend, in the same
+    /// order the spends were added in [`build_unauthorized_bundle`].
+    ///
+    /// # Note
+    /// This function is EXPENSIVE - takes ~5-10 seconds due to proof generation!
+    pub fn prove_and_sign(
+        self,
+        spend_auth_keys: &[SpendAuthorizingKey],
+        sighash: [u8; 32],
+    ) -> Result<crate::OrchardBundle, String> {
+        let mut rng = OsRng;
+        let pk = orchard_proving_key();
+
+        let proven = self
+            .inner
+            .create_proof(pk, &mut rng)
+            .map_err(|e| format!("Failed to create proof: {:?}", e))?;
+
+        let authorized = proven
+            .apply_signatures(&mut rng, sighash, spend_auth_keys)
+            .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
+
+        let mut bundle_bytes = Vec::new();
+        zcash_primitives::transaction::components::orchard::write_v5_bundle(Some(&authorized), &mut bundle_bytes)
+            .map_err(|e| format!("Failed to serialize bundle: {:?}", e))?;
+
+        crate::OrchardBundle::parse(&bundle_bytes)
+    }
+}
+
+/// Build an unauthorized (unproven, unsigned) Orchard bundle that spends
+/// real notes directly, without going through an
+/// [`crate::wallet_state::OrchardWalletState`]
+///
+/// Adds each of `spends` and `outputs` to the builder (enforcing
+/// `bundle_type`'s spends-/outputs-enabled flags), pads to whatever action
+/// count `bundle_type` requires, and returns the resulting
+/// [`UnauthorizedBundle`] - call [`UnauthorizedBundle::prove_and_sign`] to
+/// turn it into a real bundle.
+pub fn build_unauthorized_bundle(
+    anchor: Anchor,
+    bundle_type: BundleType,
+    spends: &[OrchardSpendInfo],
+    outputs: &[(Address, u64, Option<[u8; 512]>)],
+) -> Result<UnauthorizedBundle, String> {
+    let (spends_enabled, outputs_enabled) = match bundle_type {
+        BundleType::Transactional { flags, .. } => (flags.spends_enabled(), flags.outputs_enabled()),
+        BundleType::Coinbase => (false, true),
+    };
+    if !spends.is_empty() && !spends_enabled {
+        return Err("Chosen bundle type has spends disabled".to_string());
+    }
+    if !outputs.is_empty() && !outputs_enabled {
+        return Err("Chosen bundle type has outputs disabled".to_string());
+    }
+
+    let mut builder = Builder::new(bundle_type, anchor);
+
+    for spend in spends {
+        builder
+            .add_spend(spend.fvk.clone(), spend.note.clone(), spend.merkle_path.clone())
+            .map_err(|e| format!("Failed to add spend: {:?}", e))?;
+    }
+
+    for (recipient, value, memo) in outputs {
+        builder
+            .add_output(None, *recipient, NoteValue::from_raw(*value), memo.unwrap_or([0u8; 512]))
+            .map_err(|e| format!("Failed to add output: {:?}", e))?;
+    }
+
+    let unproven = builder
+        .build(&mut OsRng)
+        .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
+
+    match unproven {
+        Some((inner, _metadata)) => Ok(UnauthorizedBundle { inner }),
+        None => Err("Builder produced empty bundle".to_string()),
+    }
+}
+
 /// Create a shielded-to-shielded (z→z) Orchard bundle
 ///
 /// This creates a bundle that:
@@ -204,6 +802,9 @@ pub fn build_transparent_to_shielded(
 /// * `send_amount` - Amount to send to recipient (in drops)
 /// * `anchor` - Current Merkle tree root
 /// * `note_positions` - Positions of notes to spend in the Merkle tree (for witness paths)
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by every
+///   spend authorization signature and the binding signature; the bundle
+///   will only validate against this exact sighash
 ///
 /// # Returns
 /// Serialized bundle bytes ready to include in a transaction
@@ -221,6 +822,7 @@ pub fn build_shielded_to_shielded(
     recipient: Address,
     send_amount: u64,
     anchor: Anchor,
+    sighash: [u8; 32],
 ) -> Result<Vec<u8>, String> {
     // Parse spending key
     let sk = SpendingKey::from_bytes(*sk_bytes)
@@ -294,20 +896,19 @@ pub fn build_shielded_to_shielded(
     match unproven {
         Some((unproven_bundle, _metadata)) => {
             // Get the proving key
-            let pk = orchard::circuit::ProvingKey::build();
+            let pk = orchard_proving_key();
 
             // Create proof (EXPENSIVE!)
             let proven = unproven_bundle
-                .create_proof(&pk, &mut rng)
+                .create_proof(pk, &mut rng)
                 .map_err(|e| format!("Failed to create proof: {:?}", e))?;
 
             // Apply spend authorization signatures
-            // We need to sign with the spending key
-            let dummy_sighash = [0u8; 32];
-
-            // For now, since we don't have real spends, we pass empty sighash
+            // We don't have real spends here, so there are no spend
+            // authorizing keys, but the binding signature still commits to
+            // the real sighash.
             let authorized = proven
-                .apply_signatures(&mut rng, dummy_sighash, &[])
+                .apply_signatures(&mut rng, sighash, &[])
                 .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
 
             // Serialize the bundle
@@ -337,6 +938,11 @@ pub fn build_shielded_to_shielded(
 /// * `recipient` - Recipient address
 /// * `send_amount` - Amount to send
 /// * `note_commitments` - Commitments of notes to spend
+/// * `memo` - Optional 512-byte memo attached to the recipient's output; the
+///   change output (if any) always carries an empty memo
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by every
+///   spend authorization signature and the binding signature; the bundle
+///   will only validate against this exact sighash
 ///
 /// # Returns
 /// Serialized bundle ready for inclusion in transaction
@@ -345,6 +951,8 @@ pub fn build_shielded_to_shielded_production(
     sk_bytes: &[u8; 32],
     recipient: Address,
     send_amount: u64,
+    memo: Option<[u8; 512]>,
+    sighash: [u8; 32],
 ) -> Result<Vec<u8>, String> {
     use crate::note_manager::SpendableNote;
 
@@ -358,8 +966,8 @@ pub fn build_shielded_to_shielded_production(
     // Get anchor from tree
     let anchor = note_manager.get_anchor()?;
 
-    // Select notes to spend
-    let selected_cmxs = note_manager.select_notes(send_amount)?;
+    // Select notes to spend (native value only)
+    let selected_cmxs = note_manager.select_notes(send_amount, crate::note_manager::NATIVE_ASSET)?;
 
     // Calculate total and change
     let mut total_input = 0u64;
@@ -396,24 +1004,30 @@ pub fn build_shielded_to_shielded_production(
         ).map_err(|e| format!("Failed to add spend: {:?}", e))?;
     }
 
-    let memo = [0u8; 512];
+    let recipient_memo = memo.unwrap_or([0u8; 512]);
+    let change_memo = [0u8; 512];
+
+    // Derive our own outgoing viewing key so outputs we create can later be
+    // recovered from the chain with `try_decrypt_outgoing`, without needing
+    // to have been the recipient.
+    let ovk = fvk.to_ovk(Scope::External);
 
     // Add output to recipient
     builder.add_output(
-        None,
+        Some(ovk.clone()),
         recipient,
         NoteValue::from_raw(send_amount),
-        memo,
+        recipient_memo,
     ).map_err(|e| format!("Failed to add recipient output: {:?}", e))?;
 
     // Add change output if needed
     if change_amount > 0 {
         let change_address = get_address_from_sk(&sk, 0);
         builder.add_output(
-            None,
+            Some(ovk.clone()),
             change_address,
             NoteValue::from_raw(change_amount),
-            memo,
+            change_memo,
         ).map_err(|e| format!("Failed to add change output: {:?}", e))?;
     }
 
@@ -427,20 +1041,19 @@ pub fn build_shielded_to_shielded_production(
     match unproven {
         Some((unproven_bundle, _metadata)) => {
             // Get the proving key
-            let pk = orchard::circuit::ProvingKey::build();
+            let pk = orchard_proving_key();
 
             // Create proof (EXPENSIVE!)
             let proven = unproven_bundle
-                .create_proof(&pk, &mut rng)
+                .create_proof(pk, &mut rng)
                 .map_err(|e| format!("Failed to create proof: {:?}", e))?;
 
             // Apply signatures - need to sign with spending authorization keys
-            let dummy_sighash = [0u8; 32]; // Real sighash will be provided later
             let ask = SpendAuthorizingKey::from(&sk);
             let saks: Vec<SpendAuthorizingKey> = vec![ask]; // One SAK for all our notes
 
             let authorized = proven
-                .apply_signatures(&mut rng, dummy_sighash, &saks)
+                .apply_signatures(&mut rng, sighash, &saks)
                 .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
 
             // Serialize the bundle
@@ -469,6 +1082,13 @@ pub fn build_shielded_to_shielded_production(
 /// * `sk_bytes` - Spending key (32 bytes)
 /// * `recipient` - Recipient address
 /// * `send_amount` - Amount to send
+/// * `fee` - Fee withheld from the selected notes' total, on top of
+///   `send_amount`, when sizing the change output
+/// * `memo` - Optional 512-byte memo attached to the recipient's output; the
+///   change output (if any) always carries an empty memo
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by every
+///   spend authorization signature and the binding signature; the bundle
+///   will only validate against this exact sighash
 ///
 /// # Returns
 /// Serialized bundle ready for inclusion in transaction
@@ -477,6 +1097,36 @@ pub fn build_shielded_to_shielded_from_wallet(
     sk_bytes: &[u8; 32],
     recipient: Address,
     send_amount: u64,
+    fee: u64,
+    memo: Option<[u8; 512]>,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    build_shielded_to_shielded_from_wallet_with_rng(
+        wallet_state,
+        sk_bytes,
+        recipient,
+        send_amount,
+        fee,
+        memo,
+        sighash,
+        &mut OsRng,
+    )
+}
+
+/// Same as [`build_shielded_to_shielded_from_wallet`], but draws
+/// `build`/`create_proof`/`apply_signatures` randomness from the
+/// caller-supplied `rng` instead of always reaching for [`OsRng`] - letting a
+/// caller seed a deterministic RNG (e.g. `ChaChaRng::seed_from_u64`) to
+/// reproduce the exact same bundle bytes across runs.
+pub fn build_shielded_to_shielded_from_wallet_with_rng<R: RngCore + CryptoRng>(
+    wallet_state: &crate::wallet_state::OrchardWalletState,
+    sk_bytes: &[u8; 32],
+    recipient: Address,
+    send_amount: u64,
+    fee: u64,
+    memo: Option<[u8; 512]>,
+    sighash: [u8; 32],
+    rng: &mut R,
 ) -> Result<Vec<u8>, String> {
     // Parse spending key
     let sk = SpendingKey::from_bytes(*sk_bytes)
@@ -488,8 +1138,12 @@ pub fn build_shielded_to_shielded_from_wallet(
     // Get anchor from wallet state
     let anchor = wallet_state.get_anchor()?;
 
+    let total_needed = send_amount
+        .checked_add(fee)
+        .ok_or_else(|| "Amount plus fee overflows u64".to_string())?;
+
     // Select notes to spend
-    let selected_notes = wallet_state.select_notes(send_amount)?;
+    let selected_notes = wallet_state.select_notes(total_needed, Some(&fvk))?;
 
     // Calculate total and change
     let mut total_input = 0u64;
@@ -498,7 +1152,7 @@ pub fn build_shielded_to_shielded_from_wallet(
             .ok_or_else(|| "Amount overflow".to_string())?;
     }
 
-    let change_amount = total_input.checked_sub(send_amount)
+    let change_amount = total_input.checked_sub(total_needed)
         .ok_or_else(|| "Insufficient balance".to_string())?;
 
     // Create builder
@@ -521,46 +1175,49 @@ pub fn build_shielded_to_shielded_from_wallet(
         ).map_err(|e| format!("Failed to add spend: {:?}", e))?;
     }
 
-    let memo = [0u8; 512];
+    let recipient_memo = memo.unwrap_or([0u8; 512]);
+    let change_memo = [0u8; 512];
+
+    // Derive our own outgoing viewing key so outputs we create can later be
+    // recovered from the chain with `try_decrypt_outgoing`, without needing
+    // to have been the recipient.
+    let ovk = fvk.to_ovk(Scope::External);
 
     // Add output to recipient
     builder.add_output(
-        None,
+        Some(ovk.clone()),
         recipient,
         NoteValue::from_raw(send_amount),
-        memo,
+        recipient_memo,
     ).map_err(|e| format!("Failed to add recipient output: {:?}", e))?;
 
     // Add change output if needed
     if change_amount > 0 {
         let change_address = get_address_from_sk(&sk, 0);
         builder.add_output(
-            None,
+            Some(ovk.clone()),
             change_address,
             NoteValue::from_raw(change_amount),
-            memo,
+            change_memo,
         ).map_err(|e| format!("Failed to add change output: {:?}", e))?;
     }
 
     // Build the bundle
-    let mut rng = OsRng;
-
     let unproven = builder
-        .build(&mut rng)
+        .build(&mut *rng)
         .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
 
     match unproven {
         Some((unproven_bundle, _metadata)) => {
             // Get the proving key
-            let pk = orchard::circuit::ProvingKey::build();
+            let pk = orchard_proving_key();
 
             // Create proof (EXPENSIVE!)
             let proven = unproven_bundle
-                .create_proof(&pk, &mut rng)
+                .create_proof(pk, &mut *rng)
                 .map_err(|e| format!("Failed to create proof: {:?}", e))?;
 
             // Apply signatures - need to sign with spending authorization keys
-            let dummy_sighash = [0u8; 32]; // Real sighash will be provided later
             let ask = SpendAuthorizingKey::from(&sk);
 
             // Create one SAK per spend action
@@ -569,7 +1226,7 @@ pub fn build_shielded_to_shielded_from_wallet(
                 .collect();
 
             let authorized = proven
-                .apply_signatures(&mut rng, dummy_sighash, &saks)
+                .apply_signatures(&mut *rng, sighash, &saks)
                 .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
 
             // Serialize the bundle
@@ -588,6 +1245,345 @@ pub fn build_shielded_to_shielded_from_wallet(
     }
 }
 
+/// Production z→z bundle builder supporting multiple recipients, a per-output
+/// memo, and a caller-selected [`BundleType`]
+///
+/// [`build_shielded_to_shielded_from_wallet`] always builds via the
+/// step-by-step `Builder::add_spend`/`add_output` API and a fixed
+/// `Transactional { flags: Flags::ENABLED, bundle_required: true }` type. This
+/// instead goes through Orchard's lower-level [`orchard::builder::bundle`]
+/// free function with an explicit `Vec<SpendInfo>`/`Vec<OutputInfo>`, which is
+/// what lets a caller choose the padding/action-count policy (plain
+/// transactional vs. coinbase-style outputs-only, with spends and/or outputs
+/// independently disabled via the bundle type's `Flags`) instead of always
+/// getting the one-size-fits-all default.
+///
+/// # Arguments
+/// * `wallet_state` - Wallet state containing notes and tree
+/// * `sk_bytes` - Spending key (32 bytes)
+/// * `outputs` - `(recipient address, amount in drops, 512-byte memo)` per recipient
+/// * `fee` - Network fee (in drops); selected alongside `outputs`' total when spending notes
+/// * `bundle_type` - Padding/action-count policy; see [`BundleType`]
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by every
+///   spend authorization signature and the binding signature; the bundle
+///   will only validate against this exact sighash
+///
+/// # Returns
+/// Serialized bundle ready for inclusion in transaction
+///
+/// # Note
+/// This function is EXPENSIVE - takes ~5-10 seconds due to proof generation!
+pub fn build_shielded_to_shielded_multi_from_wallet(
+    wallet_state: &crate::wallet_state::OrchardWalletState,
+    sk_bytes: &[u8; 32],
+    outputs: &[(Address, u64, [u8; 512])],
+    fee: u64,
+    bundle_type: BundleType,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    if outputs.is_empty() {
+        return Err("Must specify at least one output".to_string());
+    }
+
+    let (spends_enabled, outputs_enabled) = match bundle_type {
+        BundleType::Transactional { flags, .. } => (flags.spends_enabled(), flags.outputs_enabled()),
+        BundleType::Coinbase => (false, true),
+    };
+    if !outputs_enabled {
+        return Err("Chosen bundle type has outputs disabled".to_string());
+    }
+
+    let sk = SpendingKey::from_bytes(*sk_bytes)
+        .into_option()
+        .ok_or_else(|| "Invalid spending key".to_string())?;
+
+    let fvk = FullViewingKey::from(&sk);
+
+    let send_total = outputs.iter().try_fold(0u64, |acc, (_, amount, _)| {
+        acc.checked_add(*amount).ok_or_else(|| "Output total overflows u64".to_string())
+    })?;
+    let total_needed = send_total.checked_add(fee)
+        .ok_or_else(|| "Output total plus fee overflows u64".to_string())?;
+
+    if total_needed > 0 && !spends_enabled {
+        return Err("Chosen bundle type has spends disabled, but the outputs require spending input value".to_string());
+    }
+
+    // Get anchor from wallet state
+    let anchor = wallet_state.get_anchor()?;
+
+    // Select notes to spend
+    let selected_notes = if total_needed > 0 {
+        wallet_state.select_notes(total_needed, Some(&fvk))?
+    } else {
+        Vec::new()
+    };
+
+    // Calculate total and change
+    let mut total_input = 0u64;
+    for note in &selected_notes {
+        total_input = total_input.checked_add(note.amount)
+            .ok_or_else(|| "Amount overflow".to_string())?;
+    }
+    let change_amount = total_input.checked_sub(total_needed)
+        .ok_or_else(|| "Insufficient balance".to_string())?;
+
+    let mut spends = Vec::with_capacity(selected_notes.len());
+    for note in &selected_notes {
+        let merkle_path = wallet_state.get_merkle_path(note)?;
+        spends.push(
+            orchard::builder::SpendInfo::new(fvk.clone(), note.note.clone(), merkle_path)
+                .map_err(|e| format!("Failed to prepare spend: {:?}", e))?,
+        );
+    }
+
+    // Derive our own outgoing viewing key so outputs we create can later be
+    // recovered from the chain with `try_decrypt_outgoing`, without needing
+    // to have been the recipient.
+    let ovk = fvk.to_ovk(Scope::External);
+
+    let mut output_infos = Vec::with_capacity(outputs.len() + 1);
+    for (recipient, amount, memo) in outputs {
+        output_infos.push(orchard::builder::OutputInfo::new(
+            Some(ovk.clone()),
+            *recipient,
+            NoteValue::from_raw(*amount),
+            *memo,
+        ));
+    }
+    if change_amount > 0 {
+        let change_address = fvk.address_at(0u32, Scope::Internal);
+        output_infos.push(orchard::builder::OutputInfo::new(
+            Some(ovk.clone()),
+            change_address,
+            NoteValue::from_raw(change_amount),
+            [0u8; 512],
+        ));
+    }
+
+    // Enforce the minimum-two-action rule on the real spends and outputs,
+    // counted before any padding - so a single real action is rejected
+    // whatever `bundle_type` is, even a `Transactional` type that would pad
+    // it - and fail clearly here rather than deep inside
+    // `orchard::builder::bundle`.
+    if spends.len() + output_infos.len() < 2 {
+        return Err("Bundle must have at least two actions (spends + outputs combined)".to_string());
+    }
+
+    let mut rng = OsRng;
+
+    let built = orchard::builder::bundle::<i64>(&mut rng, anchor, bundle_type, spends, output_infos)
+        .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
+
+    match built {
+        Some((unproven_bundle, _metadata)) => {
+            let pk = orchard_proving_key();
+
+            let proven = unproven_bundle
+                .create_proof(pk, &mut rng)
+                .map_err(|e| format!("Failed to create proof: {:?}", e))?;
+
+            let ask = SpendAuthorizingKey::from(&sk);
+            let saks: Vec<SpendAuthorizingKey> = (0..selected_notes.len())
+                .map(|_| ask.clone())
+                .collect();
+
+            let authorized = proven
+                .apply_signatures(&mut rng, sighash, &saks)
+                .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
+
+            let mut bundle_bytes = Vec::new();
+            zcash_primitives::transaction::components::orchard::write_v5_bundle(
+                Some(&authorized),
+                &mut bundle_bytes,
+            )
+            .map_err(|e| format!("Failed to serialize bundle: {:?}", e))?;
+
+            Ok(bundle_bytes)
+        }
+        None => {
+            Err("Builder produced empty bundle".to_string())
+        }
+    }
+}
+
+/// Build a single Orchard bundle that pays several shielded recipients
+/// (each with its own optional memo), draws from an explicit set of notes
+/// or auto-selects, and shields or unshields an explicit transparent value -
+/// all reconciled in one pass
+///
+/// [`build_shielded_to_shielded_multi_from_wallet`] already generalizes
+/// outputs and bundle type, but always auto-selects its spends and has no
+/// transparent leg of its own; this adds explicit spend selection and an
+/// explicit transparent value, generalizing the separate t→z / z→z / z→t
+/// functions into one API - so, for instance, a single transaction can pay
+/// several shielded recipients with distinct memos while also unshielding
+/// part of the total, which none of the per-pattern functions can express.
+///
+/// # Arguments
+/// * `wallet_state` - Wallet state containing notes and tree
+/// * `sk_bytes` - Spending key (32 bytes); authorizes any spends and
+///   receives change
+/// * `outputs` - `(recipient, amount in drops, optional 512-byte memo)` per shielded recipient
+/// * `spend_cmxs` - Specific notes to spend, by commitment - `None` auto-selects
+///   enough of the sender's notes to cover `outputs`, `fee`, and any amount
+///   being unshielded
+/// * `transparent_value` - Net value moving across the transparent boundary:
+///   negative to shield that much in from the transparent pool (on top of
+///   any spends), positive to unshield that much out to it, zero for a pure
+///   z→z transfer
+/// * `fee` - Network fee in drops, covered alongside `outputs` and any
+///   unshielded amount by the selected/auto-selected spends and any
+///   shielded-in value
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by every
+///   spend authorization signature and the binding signature; the bundle
+///   will only validate against this exact sighash
+///
+/// # Returns
+/// Serialized bundle ready for inclusion in a transaction. Any value left
+/// over once `outputs`, `fee` and `transparent_value` are covered comes back
+/// to the sender as a change output.
+///
+/// # Note
+/// This function is EXPENSIVE - takes ~5-10 seconds due to proof generation!
+pub fn build_unified_bundle(
+    wallet_state: &crate::wallet_state::OrchardWalletState,
+    sk_bytes: &[u8; 32],
+    outputs: &[(Address, u64, Option<[u8; 512]>)],
+    spend_cmxs: Option<&[[u8; 32]]>,
+    transparent_value: i64,
+    fee: u64,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    let sk = SpendingKey::from_bytes(*sk_bytes)
+        .into_option()
+        .ok_or_else(|| "Invalid spending key".to_string())?;
+    let fvk = FullViewingKey::from(&sk);
+
+    let send_total = outputs.iter().try_fold(0u64, |acc, (_, amount, _)| {
+        acc.checked_add(*amount).ok_or_else(|| "Output total overflows u64".to_string())
+    })?;
+
+    // A negative transparent_value shields that much in from the
+    // transparent pool, supplementing whatever the spends provide; a
+    // positive one unshields that much out, adding to what the spends (and
+    // any shielded-in value) must cover.
+    let unshielded_out = transparent_value.max(0) as u64;
+    let shielded_in = transparent_value
+        .checked_neg()
+        .filter(|v| *v > 0)
+        .map(|v| v as u64)
+        .unwrap_or(0);
+
+    let total_needed = send_total
+        .checked_add(fee)
+        .and_then(|v| v.checked_add(unshielded_out))
+        .ok_or_else(|| "Amount overflow".to_string())?;
+    let needed_from_spends = total_needed.saturating_sub(shielded_in);
+
+    let anchor = wallet_state.get_anchor()?;
+
+    let selected_notes = match spend_cmxs {
+        Some(cmxs) => {
+            let mut notes = Vec::with_capacity(cmxs.len());
+            for cmx in cmxs {
+                let note = wallet_state
+                    .get_note(cmx)
+                    .ok_or_else(|| format!("Note {} not found in wallet", hex::encode(cmx)))?;
+                notes.push(note);
+            }
+            notes
+        }
+        None => wallet_state.select_notes(needed_from_spends, Some(&fvk))?,
+    };
+
+    let mut spends_total = 0u64;
+    for note in &selected_notes {
+        spends_total = spends_total.checked_add(note.amount)
+            .ok_or_else(|| "Amount overflow".to_string())?;
+    }
+
+    let total_input = spends_total.checked_add(shielded_in)
+        .ok_or_else(|| "Amount overflow".to_string())?;
+    let change_amount = total_input.checked_sub(total_needed)
+        .ok_or_else(|| "Insufficient balance".to_string())?;
+
+    let mut spends = Vec::with_capacity(selected_notes.len());
+    for note in &selected_notes {
+        let merkle_path = wallet_state.get_merkle_path(note)?;
+        spends.push(
+            orchard::builder::SpendInfo::new(fvk.clone(), note.note.clone(), merkle_path)
+                .map_err(|e| format!("Failed to prepare spend: {:?}", e))?,
+        );
+    }
+
+    // Derive our own outgoing viewing key so outputs we create can later be
+    // recovered from the chain with `try_decrypt_outgoing`, without needing
+    // to have been the recipient.
+    let ovk = fvk.to_ovk(Scope::External);
+
+    let mut output_infos = Vec::with_capacity(outputs.len() + 1);
+    for (recipient, amount, memo) in outputs {
+        output_infos.push(orchard::builder::OutputInfo::new(
+            Some(ovk.clone()),
+            *recipient,
+            NoteValue::from_raw(*amount),
+            memo.unwrap_or([0u8; 512]),
+        ));
+    }
+    if change_amount > 0 {
+        let change_address = fvk.address_at(0u32, Scope::Internal);
+        output_infos.push(orchard::builder::OutputInfo::new(
+            Some(ovk),
+            change_address,
+            NoteValue::from_raw(change_amount),
+            [0u8; 512],
+        ));
+    }
+
+    if spends.is_empty() && output_infos.is_empty() {
+        return Err("Bundle would have no spends and no outputs".to_string());
+    }
+
+    let bundle_type = BundleType::Transactional {
+        flags: orchard::bundle::Flags::ENABLED,
+        bundle_required: true,
+    };
+
+    let mut rng = OsRng;
+    let built = orchard::builder::bundle::<i64>(&mut rng, anchor, bundle_type, spends, output_infos)
+        .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
+
+    match built {
+        Some((unproven_bundle, _metadata)) => {
+            let pk = orchard_proving_key();
+
+            let proven = unproven_bundle
+                .create_proof(pk, &mut rng)
+                .map_err(|e| format!("Failed to create proof: {:?}", e))?;
+
+            let ask = SpendAuthorizingKey::from(&sk);
+            let saks: Vec<SpendAuthorizingKey> = (0..selected_notes.len())
+                .map(|_| ask.clone())
+                .collect();
+
+            let authorized = proven
+                .apply_signatures(&mut rng, sighash, &saks)
+                .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
+
+            let mut bundle_bytes = Vec::new();
+            zcash_primitives::transaction::components::orchard::write_v5_bundle(
+                Some(&authorized),
+                &mut bundle_bytes,
+            )
+            .map_err(|e| format!("Failed to serialize bundle: {:?}", e))?;
+
+            Ok(bundle_bytes)
+        }
+        None => Err("Builder produced empty bundle".to_string()),
+    }
+}
+
 /// Production z→t bundle builder using OrchardWalletState
 ///
 /// This creates a bundle that:
@@ -600,6 +1596,9 @@ pub fn build_shielded_to_shielded_from_wallet(
 /// * `wallet_state` - Wallet state containing notes and tree
 /// * `sk_bytes` - Spending key (32 bytes)
 /// * `unshield_amount` - Amount to transfer to transparent pool (in drops)
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by every
+///   spend authorization signature and the binding signature; the bundle
+///   will only validate against this exact sighash
 ///
 /// # Returns
 /// Serialized bundle ready for inclusion in transaction
@@ -611,6 +1610,22 @@ pub fn build_shielded_to_transparent(
     wallet_state: &crate::wallet_state::OrchardWalletState,
     sk_bytes: &[u8; 32],
     unshield_amount: u64,
+    sighash: [u8; 32],
+) -> Result<Vec<u8>, String> {
+    build_shielded_to_transparent_with_rng(wallet_state, sk_bytes, unshield_amount, sighash, &mut OsRng)
+}
+
+/// Same as [`build_shielded_to_transparent`], but draws
+/// `build`/`create_proof`/`apply_signatures` randomness from the
+/// caller-supplied `rng` instead of always reaching for [`OsRng`] - letting a
+/// caller seed a deterministic RNG (e.g. `ChaChaRng::seed_from_u64`) to
+/// reproduce the exact same bundle bytes across runs.
+pub fn build_shielded_to_transparent_with_rng<R: RngCore + CryptoRng>(
+    wallet_state: &crate::wallet_state::OrchardWalletState,
+    sk_bytes: &[u8; 32],
+    unshield_amount: u64,
+    sighash: [u8; 32],
+    rng: &mut R,
 ) -> Result<Vec<u8>, String> {
     // Parse spending key
     let sk = SpendingKey::from_bytes(*sk_bytes)
@@ -661,8 +1676,9 @@ pub fn build_shielded_to_transparent(
     // Add change output if needed (stays in shielded pool)
     if change_amount > 0 {
         let change_address = get_address_from_sk(&sk, 0);
+        let ovk = fvk.to_ovk(Scope::External);
         builder.add_output(
-            None,
+            Some(ovk),
             change_address,
             NoteValue::from_raw(change_amount),
             memo,
@@ -670,24 +1686,21 @@ pub fn build_shielded_to_transparent(
     }
 
     // Build the bundle
-    let mut rng = OsRng;
-
     let unproven = builder
-        .build(&mut rng)
+        .build(&mut *rng)
         .map_err(|e| format!("Failed to build bundle: {:?}", e))?;
 
     match unproven {
         Some((unproven_bundle, _metadata)) => {
             // Get the proving key
-            let pk = orchard::circuit::ProvingKey::build();
+            let pk = orchard_proving_key();
 
             // Create proof (EXPENSIVE!)
             let proven = unproven_bundle
-                .create_proof(&pk, &mut rng)
+                .create_proof(pk, &mut *rng)
                 .map_err(|e| format!("Failed to create proof: {:?}", e))?;
 
             // Apply signatures - need to sign with spending authorization keys
-            let dummy_sighash = [0u8; 32]; // Real sighash will be provided later
             let ask = SpendAuthorizingKey::from(&sk);
 
             // Create one SAK per spend action
@@ -696,7 +1709,7 @@ pub fn build_shielded_to_transparent(
                 .collect();
 
             let authorized = proven
-                .apply_signatures(&mut rng, dummy_sighash, &saks)
+                .apply_signatures(&mut *rng, sighash, &saks)
                 .map_err(|e| format!("Failed to apply signatures: {:?}", e))?;
 
             // Serialize the bundle
@@ -750,7 +1763,7 @@ mod tests {
         let anchor = get_empty_anchor();
 
         // Build a bundle for 1000 drops
-        let bundle_bytes = build_transparent_to_shielded(1000, recipient, anchor)
+        let bundle_bytes = build_transparent_to_shielded(1000, recipient, anchor, [0u8; 32])
             .expect("Failed to build bundle");
 
         // Verify we can parse it back
@@ -765,7 +1778,95 @@ mod tests {
         let nullifiers = bundle.nullifiers();
         assert_eq!(nullifiers.len(), 1);
 
+        // Verify the real (non-empty) anchor made it through parsing intact
+        assert_eq!(bundle.anchor(), anchor.to_bytes());
+
+        // Round-trip: re-serialize and parse again, and check it matches byte-for-byte
+        let round_tripped_bytes = bundle.serialize();
+        assert_eq!(round_tripped_bytes, bundle_bytes);
+
+        let reparsed =
+            OrchardBundle::parse(&round_tripped_bytes).expect("Failed to re-parse round-tripped bundle");
+        assert!(reparsed.is_present());
+        assert_eq!(reparsed.num_actions(), bundle.num_actions());
+        assert_eq!(reparsed.value_balance(), bundle.value_balance());
+        assert_eq!(reparsed.anchor(), bundle.anchor());
+        assert_eq!(reparsed.nullifiers(), nullifiers);
+
         // Note: Full proof verification requires the actual transaction sighash
         // That verification happens in the transaction validation flow
     }
+
+    #[test]
+    fn test_multi_output_rejects_empty_outputs() {
+        let wallet = crate::wallet_state::OrchardWalletState::new();
+        let sk_bytes = [7u8; 32];
+
+        let result = build_shielded_to_shielded_multi_from_wallet(
+            &wallet,
+            &sk_bytes,
+            &[],
+            0,
+            BundleType::Transactional {
+                flags: orchard::bundle::Flags::ENABLED,
+                bundle_required: true,
+            },
+            [0u8; 32],
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_multi_output_rejects_outputs_when_disabled() {
+        let wallet = crate::wallet_state::OrchardWalletState::new();
+        let sk = generate_test_spending_key(9);
+        let sk_bytes = sk.to_bytes();
+        let recipient = get_address_from_sk(&sk, 0);
+
+        let result = build_shielded_to_shielded_multi_from_wallet(
+            &wallet,
+            &sk_bytes,
+            &[(recipient, 1000, [0u8; 512])],
+            0,
+            BundleType::Transactional {
+                flags: orchard::bundle::Flags::from_parts(true, false),
+                bundle_required: true,
+            },
+            [0u8; 32],
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unified_bundle_rejects_empty_spends_and_outputs() {
+        let wallet = crate::wallet_state::OrchardWalletState::new();
+        let sk_bytes = [7u8; 32];
+
+        let result = build_unified_bundle(&wallet, &sk_bytes, &[], None, 0, 0, [0u8; 32]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_unified_bundle_rejects_insufficient_balance() {
+        let wallet = crate::wallet_state::OrchardWalletState::new();
+        let sk = generate_test_spending_key(11);
+        let sk_bytes = sk.to_bytes();
+        let recipient = get_address_from_sk(&sk, 0);
+
+        // No notes in the wallet, so spending 1000 drops can never be covered.
+        let result = build_unified_bundle(
+            &wallet,
+            &sk_bytes,
+            &[(recipient, 1000, None)],
+            None,
+            0,
+            0,
+            [0u8; 32],
+        );
+
+        assert!(result.is_err());
+    }
 }
@@ -17,6 +17,24 @@ use std::io::Cursor;
 /// - `ZatBalance`: Signed value balance (compatible with i64)
 type ZcashBundle = orchard::Bundle<Authorized, ZatBalance>;
 
+/// The Halo2 verifying key used to check Orchard proofs
+///
+/// Building this is expensive, so it's built once and shared across every
+/// proof verification - single-bundle or batched - for the life of the
+/// process.
+pub(crate) fn orchard_verifying_key() -> &'static orchard::circuit::VerifyingKey {
+    static VERIFYING_KEY: std::sync::OnceLock<orchard::circuit::VerifyingKey> = std::sync::OnceLock::new();
+    VERIFYING_KEY.get_or_init(orchard::circuit::VerifyingKey::build)
+}
+
+/// Public entry point to the same process-global [`orchard::circuit::VerifyingKey`]
+/// every verification path in this crate borrows from, so an FFI caller can
+/// pre-warm it (e.g. at process startup) instead of paying the build cost on
+/// the first transaction it verifies.
+pub fn verifying_key() -> &'static orchard::circuit::VerifyingKey {
+    orchard_verifying_key()
+}
+
 /// Our wrapper around the Zcash Orchard bundle
 ///
 /// This struct maintains the real bundle internally and caches
@@ -169,47 +187,276 @@ impl OrchardBundle {
         self.inner().map(|b| b.actions().len()).unwrap_or(0)
     }
 
-    /// Verify the Halo2 proof for this bundle
+    /// Build a structured summary of this bundle for debugging and
+    /// sanity-checking - e.g. in a test like `test_build_tz_bundle`, or from
+    /// the standalone `inspect_bundle` tool - without wiring up a full
+    /// transaction validation flow
+    pub fn inspect(&self) -> BundleInspection {
+        let (spends_enabled, outputs_enabled) = match &self.inner {
+            None => (false, false),
+            Some(bundle) => (bundle.flags().spends_enabled(), bundle.flags().outputs_enabled()),
+        };
+
+        let actions = self
+            .inner()
+            .map(|bundle| {
+                bundle
+                    .actions()
+                    .iter()
+                    .map(|action| ActionInspection {
+                        nullifier: action.nullifier().to_bytes(),
+                        cmx: action.cmx().to_bytes(),
+                        // Orchard pads real spends/outputs with dummy actions
+                        // indistinguishable from real ones without the
+                        // relevant viewing key - that's the whole point of
+                        // padding for privacy, so this can't be detected
+                        // from the serialized bundle alone.
+                        is_likely_dummy: false,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        BundleInspection {
+            is_present: self.is_present(),
+            value_balance: self.value_balance(),
+            anchor: self.anchor(),
+            spends_enabled,
+            outputs_enabled,
+            num_actions: self.num_actions(),
+            is_padded_to_min_actions: self.num_actions() >= orchard::builder::MIN_ACTIONS,
+            actions,
+        }
+    }
+
+    /// Per-action fields for every action in this bundle
     ///
-    /// This is the most expensive operation (~1-2 seconds) as it
-    /// verifies the zero-knowledge proof using Halo2.
+    /// Read-only projections of `bundle.actions()`, for consumers like a
+    /// `getrawtransaction`-style JSON dump or wallet trial-decryption that
+    /// need the raw per-action fields without re-parsing the bundle
+    /// themselves. Slots in next to `nullifiers()`/`anchor()`.
+    pub fn actions(&self) -> Vec<Action> {
+        self.inner()
+            .map(|bundle| {
+                bundle
+                    .actions()
+                    .iter()
+                    .map(|action| {
+                        let encrypted_note = action.encrypted_note();
+                        let spend_auth_sig: [u8; 64] = (*action.authorization()).into();
+                        Action {
+                            cv_net: action.cv_net().to_bytes(),
+                            nullifier: action.nullifier().to_bytes(),
+                            rk: (*action.rk()).into(),
+                            cmx: action.cmx().to_bytes(),
+                            ephemeral_key: encrypted_note.epk_bytes,
+                            enc_ciphertext: encrypted_note.enc_ciphertext,
+                            out_ciphertext: encrypted_note.out_ciphertext,
+                            spend_auth_sig,
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Bundle-level flags as a single byte (bit 0: spends enabled, bit 1:
+    /// outputs enabled) - exactly the byte the ZIP-225 serialization writes
+    ///
+    /// Returns `0` if the bundle is absent.
+    pub fn flags(&self) -> u8 {
+        self.inner().map(|b| b.flags().to_byte()).unwrap_or(0)
+    }
+
+    /// The bundle's binding signature bytes, or all-zero if absent
+    pub fn binding_signature(&self) -> [u8; 64] {
+        self.inner()
+            .map(|b| (*b.authorization().binding_signature()).into())
+            .unwrap_or([0u8; 64])
+    }
+
+    /// Verify the Halo2 proof and RedPallas signatures for this bundle
+    ///
+    /// This is a thin wrapper around [`orchard::bundle::BatchValidator`] that
+    /// queues just this one bundle and runs it as a one-element batch, so a
+    /// single verification and a batch of many share the exact same
+    /// validation path (and the same cached [`orchard_verifying_key`]).
+    /// Verifying many bundles at once via a real batch (see
+    /// `OrchardBatchVerifier` in the FFI layer) is substantially cheaper
+    /// than calling this once per bundle.
     ///
     /// # Arguments
-    /// * `_sighash` - The transaction signature hash (32 bytes)
-    ///               (Note: In orchard 0.11+, sighash is verified via binding signature internally)
+    /// * `sighash` - The transaction signature hash (32 bytes)
     ///
     /// # Returns
-    /// `true` if the proof is valid, `false` otherwise
-    pub fn verify_proof(&self, _sighash: &[u8; 32]) -> bool {
+    /// `true` if the proof and signatures are valid, `false` otherwise
+    pub fn verify_proof(&self, sighash: &[u8; 32]) -> bool {
         match &self.inner {
-            None => {
-                // Empty bundle has no proof to verify
-                // This is valid (transaction with no Orchard operations)
-                true
-            }
+            // Empty bundle has no proof to verify - this is valid
+            // (transaction with no Orchard operations)
+            None => true,
             Some(bundle) => {
-                // Get the verifying key
-                // TODO: Cache this globally as it's expensive to build
-                let vk = orchard::circuit::VerifyingKey::build();
-
-                // Verify the proof against the sighash
-                // In orchard 0.11, verify_proof takes the bundle directly
-                match bundle.verify_proof(&vk) {
-                    Ok(()) => {
-                        // Also verify binding signature which includes sighash
-                        // The binding signature verification is done internally by Zcash
-                        true
-                    }
-                    Err(e) => {
-                        eprintln!("Orchard proof verification failed: {:?}", e);
-                        false
-                    }
+                let mut batch = orchard::bundle::BatchValidator::new();
+                batch.add_bundle(bundle, *sighash);
+                batch.validate(orchard_verifying_key(), rand::rngs::OsRng)
+            }
+        }
+    }
+
+    /// The proof bytes plus every per-action `spend_auth_sig` and the
+    /// binding signature, concatenated in bundle order
+    ///
+    /// This is exactly the data [`verify_proof`](Self::verify_proof)
+    /// checks - used as the input to [`crate::validation_cache::cache_key`]
+    /// so a cache hit can only happen for a bundle whose proof and
+    /// signatures genuinely match what was previously verified.
+    fn authorizing_data_bytes(&self) -> Option<Vec<u8>> {
+        let bundle = self.inner()?;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(bundle.authorization().proof().as_ref());
+        for action in bundle.actions() {
+            let sig_bytes: [u8; 64] = (*action.authorization()).into();
+            bytes.extend_from_slice(&sig_bytes);
+        }
+        let binding_sig_bytes: [u8; 64] = (*bundle.authorization().binding_signature()).into();
+        bytes.extend_from_slice(&binding_sig_bytes);
+
+        Some(bytes)
+    }
+
+    /// Same as [`verify_proof`](Self::verify_proof), but returns `true`
+    /// immediately if this exact bundle (by its proof and signatures) was
+    /// already successfully verified against `sighash` - see
+    /// [`crate::validation_cache`].
+    ///
+    /// Validating the same transaction twice (once entering the mempool,
+    /// again when its block arrives) would otherwise re-run the same
+    /// expensive Halo2 proof verification for no reason.
+    pub fn verify_proof_cached(&self, sighash: &[u8; 32]) -> bool {
+        let Some(authorizing_data) = self.authorizing_data_bytes() else {
+            // Empty bundle - nothing to cache, same as `verify_proof`.
+            return true;
+        };
+
+        let key = crate::validation_cache::cache_key(&authorizing_data, sighash);
+        if crate::validation_cache::contains(&key) {
+            return true;
+        }
+
+        let valid = self.verify_proof(sighash);
+        if valid {
+            crate::validation_cache::insert(key);
+        }
+        valid
+    }
+
+    /// Verify the Halo2 proof and RedPallas signatures, and enforce the
+    /// structural/balance consensus rules that `verify_proof`/`is_valid`
+    /// don't check on their own
+    ///
+    /// Unlike `verify_proof` (which returns a bare `bool`) and `is_valid`
+    /// (which only checks that the bundle has at least one action), this
+    /// distinguishes each way a bundle can be rejected, which is a node
+    /// needs when deciding whether something is worth logging or
+    /// banning a peer over.
+    ///
+    /// An empty bundle (no Orchard component at all) always passes.
+    ///
+    /// # Arguments
+    /// * `sighash` - The transaction signature hash (32 bytes)
+    pub fn check_consensus(&self, sighash: &[u8; 32]) -> Result<(), BundleConsensusError> {
+        let bundle = match &self.inner {
+            None => return Ok(()),
+            Some(bundle) => bundle,
+        };
+
+        let actions = bundle.actions();
+
+        if actions.len() < orchard::builder::MIN_ACTIONS {
+            return Err(BundleConsensusError::TooFewActions {
+                actions: actions.len(),
+                min_actions: orchard::builder::MIN_ACTIONS,
+            });
+        }
+
+        let mut seen_nullifiers = std::collections::HashSet::with_capacity(actions.len());
+        for action in actions {
+            let nullifier = action.nullifier().to_bytes();
+            if !seen_nullifiers.insert(nullifier) {
+                return Err(BundleConsensusError::DuplicateNullifier(nullifier));
+            }
+        }
+
+        let flags = bundle.flags();
+        let value_balance: i64 = (*bundle.value_balance()).into();
+        if !flags.spends_enabled() && !flags.outputs_enabled() && value_balance != 0 {
+            return Err(BundleConsensusError::NonZeroValueBalanceWithNoActions { value_balance });
+        }
+
+        let mut batch = orchard::bundle::BatchValidator::new();
+        batch.add_bundle(bundle, *sighash);
+        if !batch.validate(orchard_verifying_key(), rand::rngs::OsRng) {
+            // `BatchValidator` only gives a combined proof+signatures verdict,
+            // so check each `SpendAuth`/`Binding` signature individually to
+            // tell a signature failure apart from a proof failure.
+            for action in actions {
+                if action.rk().verify(sighash, action.authorization()).is_err() {
+                    return Err(BundleConsensusError::SignatureInvalid);
                 }
             }
+            if bundle
+                .binding_validating_key()
+                .verify(sighash, bundle.authorization().binding_signature())
+                .is_err()
+            {
+                return Err(BundleConsensusError::SignatureInvalid);
+            }
+            // Every signature checks out on its own, so the batch only
+            // failed because the proof itself doesn't verify.
+            return Err(BundleConsensusError::ProofInvalid);
+        }
+
+        Ok(())
+    }
+}
+
+/// Why a bundle failed [`OrchardBundle::check_consensus`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleConsensusError {
+    /// The Halo2 proof failed to verify
+    ProofInvalid,
+    /// A `SpendAuth` or `Binding` RedPallas signature failed to verify
+    SignatureInvalid,
+    /// Fewer actions than the protocol's minimum
+    TooFewActions { actions: usize, min_actions: usize },
+    /// The same nullifier appears in more than one action
+    DuplicateNullifier([u8; 32]),
+    /// A bundle with spends and outputs both disabled must carry a zero
+    /// value balance
+    NonZeroValueBalanceWithNoActions { value_balance: i64 },
+}
+
+impl std::fmt::Display for BundleConsensusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleConsensusError::ProofInvalid => write!(f, "Orchard proof verification failed"),
+            BundleConsensusError::SignatureInvalid => write!(f, "Orchard signature verification failed"),
+            BundleConsensusError::TooFewActions { actions, min_actions } => {
+                write!(f, "Bundle has {actions} action(s), fewer than the required minimum of {min_actions}")
+            }
+            BundleConsensusError::DuplicateNullifier(nullifier) => {
+                write!(f, "Duplicate nullifier in bundle: {}", hex::encode(nullifier))
+            }
+            BundleConsensusError::NonZeroValueBalanceWithNoActions { value_balance } => {
+                write!(f, "Bundle with spends and outputs disabled has non-zero value balance: {value_balance}")
+            }
         }
     }
 }
 
+impl std::error::Error for BundleConsensusError {}
+
 impl Default for OrchardBundle {
     fn default() -> Self {
         Self::empty()
@@ -227,6 +474,145 @@ impl std::fmt::Debug for OrchardBundle {
     }
 }
 
+/// Read-only view of a single bundle action's raw fields, returned by
+/// [`OrchardBundle::actions`]
+#[derive(Debug, Clone)]
+pub struct Action {
+    pub cv_net: [u8; 32],
+    pub nullifier: [u8; 32],
+    pub rk: [u8; 32],
+    pub cmx: [u8; 32],
+    pub ephemeral_key: [u8; 32],
+    pub enc_ciphertext: [u8; 580],
+    pub out_ciphertext: [u8; 80],
+    pub spend_auth_sig: [u8; 64],
+}
+
+/// Structured summary of a parsed bundle, returned by [`OrchardBundle::inspect`]
+#[derive(Debug, Clone)]
+pub struct BundleInspection {
+    pub is_present: bool,
+    pub value_balance: i64,
+    pub anchor: [u8; 32],
+    pub spends_enabled: bool,
+    pub outputs_enabled: bool,
+    pub num_actions: usize,
+    pub is_padded_to_min_actions: bool,
+    pub actions: Vec<ActionInspection>,
+}
+
+/// Per-action fields surfaced by [`BundleInspection`]
+#[derive(Debug, Clone)]
+pub struct ActionInspection {
+    pub nullifier: [u8; 32],
+    pub cmx: [u8; 32],
+    /// Always `false` - a dummy action is indistinguishable from a real one
+    /// without the relevant viewing key, by design. Kept as a field so a
+    /// future decryption-aware caller has somewhere to put a real answer.
+    pub is_likely_dummy: bool,
+}
+
+impl std::fmt::Display for BundleInspection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.is_present {
+            return writeln!(f, "Orchard bundle: absent (no shielded component)");
+        }
+
+        writeln!(f, "Orchard bundle:")?;
+        writeln!(f, "  anchor:          {}", hex::encode(self.anchor))?;
+        writeln!(f, "  value_balance:   {}", self.value_balance)?;
+        writeln!(f, "  flags:           spends_enabled={} outputs_enabled={}", self.spends_enabled, self.outputs_enabled)?;
+        writeln!(f, "  actions:         {} (padded to MIN_ACTIONS: {})", self.num_actions, self.is_padded_to_min_actions)?;
+        for (i, action) in self.actions.iter().enumerate() {
+            writeln!(
+                f,
+                "    [{i}] nullifier={} cmx={} likely_dummy={}",
+                hex::encode(action.nullifier),
+                hex::encode(action.cmx),
+                action.is_likely_dummy,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Property-test generators for [`OrchardBundle`]
+///
+/// Mirrors the `test-dependencies` feature in the upstream `orchard` crate
+/// (which gates its own `arb_address`/`arb_spending_key` strategies the same
+/// way): kept behind a feature so downstream crates can pull in `proptest`
+/// strategies for fuzzing bundle handling without paying the `proptest`
+/// dependency cost in ordinary builds.
+///
+/// Building a genuinely "Authorized" bundle means running the real builder
+/// and Halo2 prover, so these strategies are not cheap - a property test
+/// using them should keep `proptest::test_runner::Config::cases` small.
+#[cfg(feature = "test-dependencies")]
+pub mod testing {
+    use super::OrchardBundle;
+    use orchard::builder::{Builder, BundleType};
+    use orchard::value::NoteValue;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use rand::rngs::OsRng;
+
+    prop_compose! {
+        /// A randomized, fully proven and authorized transparent-to-shielded
+        /// bundle: 1-4 outputs of randomized value to a randomized address,
+        /// built against the empty anchor.
+        ///
+        /// Every bundle this produces is present and satisfies
+        /// [`OrchardBundle::is_valid`]; it round-trips through
+        /// [`OrchardBundle::serialize`]/[`OrchardBundle::parse`] before being
+        /// handed back, so callers exercise the same wire format production
+        /// code sees.
+        pub fn arb_bundle()(
+            seed in any::<u8>(),
+            num_outputs in 1usize..=4,
+            values in vec(1u64..1_000_000_000, 4),
+        ) -> OrchardBundle {
+            let sk = crate::bundle_builder::generate_test_spending_key(seed);
+            let recipient = crate::bundle_builder::get_address_from_sk(&sk, 0);
+            let anchor = crate::bundle_builder::get_empty_anchor();
+
+            let mut builder = Builder::new(BundleType::Coinbase, anchor);
+            for value in values.into_iter().take(num_outputs) {
+                builder
+                    .add_output(None, recipient, NoteValue::from_raw(value), [0u8; 512])
+                    .expect("well-formed output");
+            }
+
+            let (unproven, _) = builder
+                .build(&mut OsRng)
+                .expect("build succeeds")
+                .expect("at least one output was added");
+            let pk = crate::bundle_builder::orchard_proving_key();
+            let proven = unproven
+                .create_proof(pk, &mut OsRng)
+                .expect("proof generation succeeds");
+            // t->z has no spends, so no spend authorization signatures are needed.
+            let authorized = proven
+                .apply_signatures(&mut OsRng, [0u8; 32], &[])
+                .expect("applying signatures succeeds");
+
+            let mut bytes = Vec::new();
+            zcash_primitives::transaction::components::orchard::write_v5_bundle(Some(&authorized), &mut bytes)
+                .expect("serialization cannot fail");
+            OrchardBundle::parse(&bytes).expect("bundle round-trips through parse")
+        }
+    }
+
+    prop_compose! {
+        /// Alias for [`arb_bundle`] matching the `arb_authorized_bundle`
+        /// naming used by the upstream `orchard` crate's own
+        /// `test-dependencies` strategies - every bundle [`arb_bundle`]
+        /// produces is already proven and authorized.
+        pub fn arb_authorized_bundle()(bundle in arb_bundle()) -> OrchardBundle {
+            bundle
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
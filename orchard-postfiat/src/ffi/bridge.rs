@@ -15,27 +15,101 @@ pub struct OrchardWalletState {
 }
 
 /// Batch verifier for multiple Orchard bundles
+///
+/// Queues each bundle's Halo2 proof instances and RedPallas signatures into
+/// an `orchard::bundle::BatchValidator`, then checks them as a single batch -
+/// this is dramatically faster than verifying bundles one at a time, since
+/// the multiopen argument and signatures are combined with random linear
+/// combinations.
+///
+/// Also enforces the consensus rule that no nullifier may appear twice
+/// across the bundles being validated together - a transaction whose
+/// Orchard bundle(s) spend the same note twice must fail regardless of
+/// whether each bundle's proof and signatures are individually valid.
 pub struct OrchardBatchVerifier {
-    // Placeholder for now
-    bundles: Vec<(Box<OrchardBundle>, [u8; 32])>,
+    validator: orchard::bundle::BatchValidator,
+    /// Set if any added bundle had no inner `orchard::Bundle` (empty/absent),
+    /// which must fail verification rather than be silently skipped
+    has_missing_bundle: bool,
+    /// Nullifiers seen so far across every added bundle, to catch
+    /// double-spends within the same batch
+    seen_nullifiers: std::collections::HashSet<[u8; 32]>,
+    /// Set once a nullifier has been observed more than once
+    has_duplicate_nullifier: bool,
 }
 
 impl OrchardBatchVerifier {
     pub fn new() -> Self {
         Self {
-            bundles: Vec::new(),
+            validator: orchard::bundle::BatchValidator::new(),
+            has_missing_bundle: false,
+            seen_nullifiers: std::collections::HashSet::new(),
+            has_duplicate_nullifier: false,
         }
     }
 
     pub fn add(&mut self, bundle: Box<OrchardBundle>, sighash: [u8; 32]) {
-        self.bundles.push((bundle, sighash));
+        match bundle.inner() {
+            Some(inner) => {
+                for nullifier in bundle.nullifiers() {
+                    if !self.seen_nullifiers.insert(nullifier) {
+                        self.has_duplicate_nullifier = true;
+                    }
+                }
+                self.validator.add_bundle(inner, sighash)
+            }
+            None => self.has_missing_bundle = true,
+        }
     }
 
     pub fn verify(self) -> bool {
-        // Stub implementation - always return true for now
-        // TODO: Implement actual batch verification
-        true
+        if self.has_missing_bundle || self.has_duplicate_nullifier {
+            return false;
+        }
+
+        self.validator
+            .validate(crate::bundle_real::orchard_verifying_key(), rand::rngs::OsRng)
+    }
+}
+
+/// Queues bundles for [`orchard_wallet_state_batch_scan`]
+///
+/// cxx can't pass a slice of opaque bundle handles across the FFI boundary in
+/// one call, so - mirroring [`OrchardBatchVerifier`]'s add-then-finalize shape -
+/// C++ calls [`OrchardScanBatch::add`] once per bundle (cloning it first via
+/// `orchard_bundle_box_clone` if it still needs its own copy afterward) and
+/// then hands the accumulated batch to the wallet for one combined trial
+/// decryption pass.
+pub struct OrchardScanBatch {
+    entries: Vec<(Box<OrchardBundle>, u32, [u8; 32])>,
+}
+
+impl OrchardScanBatch {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
     }
+
+    pub fn add(&mut self, bundle: Box<OrchardBundle>, ledger_seq: u32, tx_hash: [u8; 32]) {
+        self.entries.push((bundle, ledger_seq, tx_hash));
+    }
+}
+
+/// A proven, unsigned Orchard bundle waiting on external RedPallas
+/// signatures
+///
+/// Produced by [`orchard_build_unauthorized`] once spends are selected and
+/// the Halo2 proof is generated - everything a signer needs except the
+/// spend authorization signatures themselves, so a spending key never has
+/// to enter this crate. Unlike [`OrchardBundle`], this intermediate
+/// proof-but-unsigned state has no stable wire encoding in the `orchard`
+/// crate (signatures aren't assembled yet), so it's handed across the FFI
+/// boundary as an opaque boxed handle rather than serialized bytes.
+pub struct UnauthorizedOrchardBundle {
+    bundle: orchard::Bundle<
+        orchard::builder::InProgress<orchard::circuit::Proof, orchard::builder::PartiallyAuthorized>,
+        i64,
+    >,
+    sighash: [u8; 32],
 }
 
 #[cxx::bridge]
@@ -46,10 +120,18 @@ pub mod ffi {
         pub message: String,
     }
 
+    /// Outcome of scanning one bundle via [`orchard_wallet_state_try_decrypt_notes`]
+    #[derive(Debug)]
+    pub struct WalletScanResult {
+        pub notes_received: usize,
+        pub notes_spent: usize,
+    }
+
     // Opaque Rust types exposed to C++
     extern "Rust" {
         type OrchardBundle;
         type OrchardBatchVerifier;
+        type OrchardScanBatch;
 
         // Bundle parsing and serialization
         fn orchard_bundle_parse(data: &[u8]) -> Result<Box<OrchardBundle>>;
@@ -73,6 +155,7 @@ pub mod ffi {
             bundle: &OrchardBundle,
             sighash: &[u8; 32]
         ) -> bool;
+        fn orchard_prewarm_verifying_key();
 
         // Batch verification
         fn orchard_batch_verify_init() -> Box<OrchardBatchVerifier>;
@@ -92,7 +175,8 @@ pub mod ffi {
         fn orchard_test_build_transparent_to_shielded(
             amount_drops: u64,
             recipient_addr_bytes: &[u8],
-            anchor: &[u8; 32]
+            anchor: &[u8; 32],
+            sighash: [u8; 32]
         ) -> Result<Vec<u8>>;
 
         // Viewing key operations (for testing)
@@ -102,8 +186,14 @@ pub mod ffi {
             action_index: usize,
             fvk_bytes: &[u8]
         ) -> Result<u64>;
+        fn orchard_test_try_decrypt_note_with_memo(
+            bundle: &OrchardBundle,
+            action_index: usize,
+            fvk_bytes: &[u8]
+        ) -> Result<Vec<u8>>;
         fn orchard_test_try_decrypt_note_from_ciphertext(
             encrypted_note: &[u8],
+            nullifier_bytes: &[u8; 32],
             cmx_bytes: &[u8; 32],
             ephemeral_key_bytes: &[u8; 32],
             fvk_bytes: &[u8]
@@ -114,6 +204,13 @@ pub mod ffi {
             fvk_bytes: &[u8]
         ) -> Result<Vec<u8>>;
 
+        // Sender-side output recovery (audit own sends without the recipient's IVK)
+        fn orchard_try_recover_output(
+            bundle: &OrchardBundle,
+            action_index: usize,
+            ovk_bytes: &[u8]
+        ) -> Result<Vec<u8>>;
+
         // Production note management and z->z transactions
         type NoteManager;
 
@@ -124,13 +221,21 @@ pub mod ffi {
             cmx: &[u8; 32],
             nullifier: &[u8; 32],
             ledger_seq: u32,
-            tx_hash: &[u8; 32]
+            tx_hash: &[u8; 32],
+            output_index: u32
         ) -> Result<()>;
+        fn orchard_note_serialize(
+            bundle: &OrchardBundle,
+            action_index: usize,
+            fvk_bytes: &[u8]
+        ) -> Result<Vec<u8>>;
         fn orchard_note_manager_mark_spent(
             manager: &mut NoteManager,
             nullifier: &[u8; 32]
         );
         fn orchard_note_manager_get_balance(manager: &NoteManager) -> u64;
+        fn orchard_note_manager_get_asset_balance(manager: &NoteManager, asset_id: &[u8; 32]) -> u64;
+        fn orchard_note_manager_list_asset_ids(manager: &NoteManager) -> Vec<u8>;
         fn orchard_note_manager_note_count(manager: &NoteManager) -> usize;
         fn orchard_note_manager_get_anchor(manager: &NoteManager) -> Result<Vec<u8>>;
         fn orchard_note_manager_decrypt_and_add_note(
@@ -146,7 +251,9 @@ pub mod ffi {
             manager: &NoteManager,
             sk_bytes: &[u8],
             recipient_addr_bytes: &[u8],
-            send_amount: u64
+            send_amount: u64,
+            memo: &[u8],
+            sighash: [u8; 32]
         ) -> Result<Vec<u8>>;
 
         // Wallet state management (Zcash-style server-side wallet)
@@ -155,13 +262,30 @@ pub mod ffi {
         fn orchard_wallet_state_new() -> Box<OrchardWalletState>;
         fn orchard_wallet_state_reset(wallet: &mut OrchardWalletState);
 
+        // Persistence (restart/restore without a full rescan)
+        fn orchard_wallet_state_serialize(wallet: &OrchardWalletState) -> Vec<u8>;
+        fn orchard_wallet_state_deserialize(bytes: &[u8]) -> Result<Box<OrchardWalletState>>;
+
         // IVK management
         fn orchard_wallet_state_add_ivk(wallet: &mut OrchardWalletState, ivk_bytes: &[u8]) -> Result<()>;
+        fn orchard_wallet_state_add_fvk(wallet: &mut OrchardWalletState, fvk_bytes: &[u8]) -> Result<()>;
         fn orchard_wallet_state_remove_ivk(wallet: &mut OrchardWalletState, ivk_bytes: &[u8]) -> Result<()>;
         fn orchard_wallet_state_get_ivk_count(wallet: &OrchardWalletState) -> usize;
 
         // Balance and notes
         fn orchard_wallet_state_get_balance(wallet: &OrchardWalletState) -> u64;
+        fn orchard_wallet_state_get_asset_balance(wallet: &OrchardWalletState, asset_id: &[u8; 32]) -> u64;
+        fn orchard_wallet_state_list_asset_ids(wallet: &OrchardWalletState) -> Vec<u8>;
+        fn orchard_wallet_state_get_balance_with_confirmations(
+            wallet: &OrchardWalletState,
+            min_confirmations: u32,
+            chain_tip_seq: u32
+        ) -> u64;
+        fn orchard_wallet_state_get_balance_breakdown(
+            wallet: &OrchardWalletState,
+            min_confirmations: u32,
+            chain_tip_seq: u32
+        ) -> Vec<u8>;
         fn orchard_wallet_state_get_note_count(wallet: &OrchardWalletState, include_spent: bool) -> usize;
         fn orchard_wallet_state_get_note(wallet: &OrchardWalletState, cmx: &[u8; 32]) -> Result<Vec<u8>>;
 
@@ -183,15 +307,33 @@ pub mod ffi {
             bundle: &OrchardBundle,
             ledger_seq: u32,
             tx_hash: &[u8; 32]
-        ) -> Result<usize>;
+        ) -> Result<WalletScanResult>;
         fn orchard_wallet_state_mark_spent(wallet: &mut OrchardWalletState, nullifier: &[u8; 32]);
 
+        // Batched scanning across many bundles at once
+        fn orchard_scan_batch_init() -> Box<OrchardScanBatch>;
+        fn orchard_scan_batch_add(
+            batch: &mut OrchardScanBatch,
+            bundle: Box<OrchardBundle>,
+            ledger_seq: u32,
+            tx_hash: [u8; 32]
+        );
+        fn orchard_wallet_state_batch_scan(
+            wallet: &mut OrchardWalletState,
+            batch: Box<OrchardScanBatch>
+        ) -> Result<usize>;
+
+        // Witness retrieval for spending wallet-owned notes
+        fn orchard_wallet_state_get_witness(wallet: &OrchardWalletState, cmx: &[u8; 32]) -> Result<Vec<u8>>;
+        fn orchard_wallet_state_witness_anchor(wallet: &OrchardWalletState, cmx: &[u8; 32]) -> Result<Vec<u8>>;
+
         // Checkpointing
         fn orchard_wallet_state_checkpoint(wallet: &mut OrchardWalletState, ledger_seq: u32);
         fn orchard_wallet_state_last_checkpoint(wallet: &OrchardWalletState) -> u32;
+        fn orchard_wallet_state_rewind(wallet: &mut OrchardWalletState, target_ledger_seq: u32) -> Result<u32>;
 
         // Key derivation utilities
-        fn orchard_derive_ivk_from_fvk(fvk_bytes: &[u8]) -> Result<Vec<u8>>;
+        fn orchard_derive_ivk_from_fvk(fvk_bytes: &[u8], scope: u8) -> Result<Vec<u8>>;
 
         // Wallet-based bundle building (PRODUCTION)
         fn orchard_wallet_build_z_to_z(
@@ -199,13 +341,63 @@ pub mod ffi {
             sk_bytes: &[u8],
             recipient_addr_bytes: &[u8],
             send_amount: u64,
-            fee: u64
+            fee: u64,
+            memo: &[u8],
+            sighash: [u8; 32]
+        ) -> Result<Vec<u8>>;
+        fn orchard_wallet_build_z_to_z_multi(
+            wallet: &OrchardWalletState,
+            sk_bytes: &[u8],
+            outputs: &[u8],
+            fee: u64,
+            bundle_type: u8,
+            sighash: [u8; 32]
         ) -> Result<Vec<u8>>;
         fn orchard_wallet_build_z_to_t(
             wallet: &OrchardWalletState,
             sk_bytes: &[u8],
             unshield_amount: u64,
-            fee: u64
+            fee: u64,
+            sighash: [u8; 32]
+        ) -> Result<Vec<u8>>;
+
+        // Two-phase bundle construction for external/hardware signers: build
+        // and prove without ever seeing a spending key, sign outside this
+        // crate, then attach the resulting signatures.
+        type UnauthorizedOrchardBundle;
+
+        fn orchard_build_unauthorized(
+            wallet: &OrchardWalletState,
+            fvk_bytes: &[u8],
+            recipient_addr_bytes: &[u8],
+            send_amount: u64,
+            fee: u64,
+            memo: &[u8],
+            sighash: [u8; 32]
+        ) -> Result<Box<UnauthorizedOrchardBundle>>;
+        fn orchard_bundle_sighash(bundle: &UnauthorizedOrchardBundle) -> [u8; 32];
+        fn orchard_apply_signatures(
+            bundle: Box<UnauthorizedOrchardBundle>,
+            signatures: &[u8]
+        ) -> Result<Vec<u8>>;
+
+        // Same staged flow, named to match the `_z_to_z` production builders
+        // and (for the apply step) cross-checking the caller's claimed
+        // verification keys against the bundle's actual ones before signing.
+        fn orchard_wallet_build_unauthorized_z_to_z(
+            wallet: &OrchardWalletState,
+            fvk_bytes: &[u8],
+            recipient_addr_bytes: &[u8],
+            send_amount: u64,
+            fee: u64,
+            memo: &[u8],
+            sighash: [u8; 32]
+        ) -> Result<Box<UnauthorizedOrchardBundle>>;
+        fn orchard_unauthorized_bundle_sighash(bundle: &UnauthorizedOrchardBundle) -> [u8; 32];
+        fn orchard_unauthorized_bundle_apply_signatures(
+            bundle: Box<UnauthorizedOrchardBundle>,
+            rk_list: &[u8],
+            signatures: &[u8]
         ) -> Result<Vec<u8>>;
     }
 }
@@ -241,6 +433,12 @@ pub fn orchard_bundle_is_valid(bundle: &OrchardBundle) -> bool {
 
 /// Get the value balance (net flow in/out of shielded pool)
 /// Positive = net outflow (z->t), Negative = net inflow (t->z)
+///
+/// This crate does not yet build against a ZSA-enabled `orchard` (see
+/// [`crate::note_manager::AssetId`]), so `orchard::Action` here carries no
+/// per-action `AssetBase` to surface - this balance is necessarily all
+/// native-asset value. Enforcing per-asset conservation on parsed bundles is
+/// blocked on that dependency upgrade, not on anything in this wrapper.
 pub fn orchard_bundle_get_value_balance(bundle: &OrchardBundle) -> i64 {
     bundle.value_balance()
 }
@@ -295,6 +493,13 @@ pub fn orchard_verify_bundle_proof(
     bundle.verify_proof(sighash)
 }
 
+/// Build the global Orchard verifying key now, rather than lazily on the
+/// first bundle verified - call this once at process startup to avoid
+/// paying the build cost on the critical path of the first transaction.
+pub fn orchard_prewarm_verifying_key() {
+    let _ = crate::verifying_key();
+}
+
 /// Initialize a new batch verifier
 pub fn orchard_batch_verify_init() -> Box<OrchardBatchVerifier> {
     Box::new(OrchardBatchVerifier::new())
@@ -314,6 +519,21 @@ pub fn orchard_batch_verify_finalize(verifier: Box<OrchardBatchVerifier>) -> boo
     verifier.verify()
 }
 
+/// Initialize a new scan batch for [`orchard_wallet_state_batch_scan`]
+pub fn orchard_scan_batch_init() -> Box<OrchardScanBatch> {
+    Box::new(OrchardScanBatch::new())
+}
+
+/// Queue a bundle into a scan batch
+pub fn orchard_scan_batch_add(
+    batch: &mut OrchardScanBatch,
+    bundle: Box<OrchardBundle>,
+    ledger_seq: u32,
+    tx_hash: [u8; 32],
+) {
+    batch.add(bundle, ledger_seq, tx_hash);
+}
+
 //------------------------------------------------------------------------------
 // Bundle Building Functions (for Testing)
 //------------------------------------------------------------------------------
@@ -363,6 +583,7 @@ pub fn orchard_test_get_address_from_sk(sk_bytes: &[u8]) -> anyhow::Result<Vec<u
 /// * `amount_drops` - Amount in drops (1 XRP = 1,000,000 drops)
 /// * `recipient_addr_bytes` - Raw Orchard address bytes (43 bytes)
 /// * `anchor` - Current Merkle tree root (32 bytes)
+/// * `sighash` - The enclosing transaction's ZIP-244 sighash, signed by the binding signature
 ///
 /// # Returns
 /// Serialized bundle bytes ready to include in a transaction
@@ -370,6 +591,7 @@ pub fn orchard_test_build_transparent_to_shielded(
     amount_drops: u64,
     recipient_addr_bytes: &[u8],
     anchor: &[u8; 32],
+    sighash: [u8; 32],
 ) -> anyhow::Result<Vec<u8>> {
     use orchard::{Address, Anchor};
 
@@ -386,7 +608,7 @@ pub fn orchard_test_build_transparent_to_shielded(
         .ok_or_else(|| anyhow::anyhow!("Invalid anchor"))?;
 
     // Build the bundle
-    crate::bundle_builder::build_transparent_to_shielded(amount_drops, recipient, anchor)
+    crate::bundle_builder::build_transparent_to_shielded(amount_drops, recipient, anchor, sighash)
         .map_err(|e| anyhow::anyhow!("Failed to build bundle: {}", e))
 }
 
@@ -443,12 +665,69 @@ pub fn orchard_test_try_decrypt_note(
         .ok_or_else(|| anyhow::anyhow!("Failed to decrypt note - may not be for this viewing key"))
 }
 
-/// Try to decrypt a note from raw encrypted ciphertext
+/// Try to decrypt a note and return its plaintext memo
+///
+/// Same trial decryption as [`orchard_test_try_decrypt_note`], but returns
+/// the 512-byte memo field instead of the value - useful for reading back
+/// structured payloads attached via a build function's `memo` argument.
+///
+/// # Arguments
+/// * `bundle` - The Orchard bundle containing the note
+/// * `action_index` - Index of the action to decrypt (0-based)
+/// * `fvk_bytes` - Full viewing key bytes (96 bytes)
+///
+/// # Returns
+/// The 512-byte plaintext memo if decryption succeeds
+pub fn orchard_test_try_decrypt_note_with_memo(
+    bundle: &OrchardBundle,
+    action_index: usize,
+    fvk_bytes: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    use orchard::keys::{FullViewingKey, PreparedIncomingViewingKey, Scope};
+    use zcash_note_encryption::try_note_decryption;
+
+    let fvk_array: [u8; 96] = fvk_bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid full viewing key length, expected 96 bytes"))?;
+
+    let fvk = Option::from(FullViewingKey::from_bytes(&fvk_array))
+        .ok_or_else(|| anyhow::anyhow!("Invalid full viewing key"))?;
+
+    let inner = bundle.inner()
+        .ok_or_else(|| anyhow::anyhow!("Bundle is empty"))?;
+    let action = inner.actions().get(action_index)
+        .ok_or_else(|| anyhow::anyhow!("Action index out of bounds"))?;
+
+    let ivk = PreparedIncomingViewingKey::new(&fvk.to_ivk(Scope::External));
+    let domain = orchard::note_encryption::OrchardDomain::for_action(action);
+    let (_note, _addr, memo) = try_note_decryption(&domain, &ivk, action)
+        .ok_or_else(|| anyhow::anyhow!("Failed to decrypt note - may not be for this viewing key"))?;
+
+    Ok(memo.to_vec())
+}
+
+/// Parse an optional memo argument from the FFI boundary
+///
+/// An empty slice means "no memo" (the output gets an all-zero memo field);
+/// anything else must be exactly 512 bytes, matching [`orchard::builder::Builder::add_output`]'s memo field.
+fn parse_optional_memo(memo: &[u8]) -> anyhow::Result<Option<[u8; 512]>> {
+    if memo.is_empty() {
+        return Ok(None);
+    }
+    memo.try_into()
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("Memo must be exactly 512 bytes, got {}", memo.len()))
+}
+
+/// Try to decrypt a note from the compact per-action fields stored in
+/// ledger state, without needing the full Orchard bundle
 ///
 /// This is used to decrypt notes retrieved from ledger state.
 ///
 /// # Arguments
-/// * `encrypted_note` - The 580-byte encrypted note ciphertext
+/// * `encrypted_note` - The encrypted output ciphertext; only the leading
+///   `COMPACT_NOTE_SIZE` (52) bytes are used
+/// * `nullifier_bytes` - The 32-byte nullifier of the note spent by this action
 /// * `cmx_bytes` - The 32-byte note commitment
 /// * `ephemeral_key_bytes` - The 32-byte ephemeral public key
 /// * `fvk_bytes` - Full viewing key bytes (96 bytes)
@@ -457,6 +736,7 @@ pub fn orchard_test_try_decrypt_note(
 /// Note value in drops if decryption succeeds
 pub fn orchard_test_try_decrypt_note_from_ciphertext(
     encrypted_note: &[u8],
+    nullifier_bytes: &[u8; 32],
     cmx_bytes: &[u8; 32],
     ephemeral_key_bytes: &[u8; 32],
     fvk_bytes: &[u8],
@@ -473,6 +753,7 @@ pub fn orchard_test_try_decrypt_note_from_ciphertext(
     // Try to decrypt
     crate::bundle_builder::try_decrypt_note_from_ciphertext(
         encrypted_note,
+        nullifier_bytes,
         cmx_bytes,
         ephemeral_key_bytes,
         &fvk,
@@ -526,6 +807,82 @@ pub fn orchard_test_compute_note_nullifier(
     Ok(nullifier.to_vec())
 }
 
+/// Derive an `OutgoingViewingKey` from the bytes supplied to
+/// [`orchard_try_recover_output`] - either a 96-byte full viewing key (the
+/// external OVK is derived from it) or a raw 32-byte OVK.
+fn parse_ovk(ovk_bytes: &[u8]) -> anyhow::Result<orchard::keys::OutgoingViewingKey> {
+    use orchard::keys::{FullViewingKey, OutgoingViewingKey, Scope};
+
+    match ovk_bytes.len() {
+        32 => {
+            let ovk_array: [u8; 32] = ovk_bytes.try_into().unwrap();
+            Ok(OutgoingViewingKey::from(ovk_array))
+        }
+        96 => {
+            let fvk_array: [u8; 96] = ovk_bytes.try_into().unwrap();
+            let fvk = FullViewingKey::from_bytes(&fvk_array)
+                .ok_or_else(|| anyhow::anyhow!("Invalid full viewing key"))?;
+            Ok(fvk.to_ovk(Scope::External))
+        }
+        other => Err(anyhow::anyhow!(
+            "OVK bytes must be 32 (raw OVK) or 96 (full viewing key) bytes, got {}",
+            other
+        )),
+    }
+}
+
+/// Recover an output sent by us, using the outgoing viewing key
+///
+/// Unlike [`orchard_test_try_decrypt_note`], this doesn't need the
+/// recipient's incoming viewing key - only the OVK used when the bundle was
+/// built, which this crate's own build functions always derive from the
+/// sender's spending key. This lets a sender reconstruct exactly what was
+/// sent to each recipient (value, address, memo) for compliance/audit or to
+/// rebuild wallet history after a restore, without ever holding the
+/// recipient's spending key.
+///
+/// # Arguments
+/// * `bundle` - The Orchard bundle containing the action
+/// * `action_index` - Index of the action in the bundle
+/// * `ovk_bytes` - Raw OVK (32 bytes) or full viewing key (96 bytes) to
+///   derive the external OVK from
+///
+/// # Returns
+/// JSON-encoded `{value, recipient_addr, memo}`, with `recipient_addr` and
+/// `memo` hex-encoded
+pub fn orchard_try_recover_output(
+    bundle: &OrchardBundle,
+    action_index: usize,
+    ovk_bytes: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    use zcash_note_encryption::try_output_recovery_with_ovk;
+
+    let ovk = parse_ovk(ovk_bytes)?;
+
+    let inner = bundle.inner()
+        .ok_or_else(|| anyhow::anyhow!("Bundle is empty"))?;
+    let action = inner.actions().get(action_index)
+        .ok_or_else(|| anyhow::anyhow!("Action index out of bounds"))?;
+
+    let domain = orchard::note_encryption::OrchardDomain::for_action(action);
+    let (note, recipient, memo) = try_output_recovery_with_ovk(
+        &domain,
+        &ovk,
+        action,
+        action.cv_net(),
+        &action.encrypted_note().out_ciphertext,
+    ).ok_or_else(|| anyhow::anyhow!("Failed to recover output - not sent with this OVK"))?;
+
+    let json = serde_json::json!({
+        "value": note.value().inner(),
+        "recipient_addr": hex::encode(recipient.to_raw_address_bytes()),
+        "memo": hex::encode(memo),
+    });
+
+    serde_json::to_vec(&json)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize recovered output: {}", e))
+}
+
 /// Build a shielded-to-shielded (z→z) bundle for testing
 ///
 /// This generates a REAL Orchard bundle with valid proofs.
@@ -553,6 +910,11 @@ pub fn orchard_note_manager_new() -> Box<NoteManager> {
 }
 
 /// Add a received note to the manager
+///
+/// `note_bytes` must be the format produced by
+/// [`crate::note_manager::serialize_note`] - the reconstructed note is
+/// validated against `cmx` before being stored, so a scanner can rehydrate
+/// notes it persisted earlier without re-running trial decryption.
 pub fn orchard_note_manager_add_note(
     manager: &mut NoteManager,
     note_bytes: &[u8],
@@ -560,15 +922,49 @@ pub fn orchard_note_manager_add_note(
     nullifier: &[u8; 32],
     ledger_seq: u32,
     tx_hash: &[u8; 32],
+    output_index: u32,
 ) -> anyhow::Result<()> {
-    // Deserialize the note
-    use orchard::note::Note;
+    let note = crate::note_manager::deserialize_note(note_bytes, cmx)
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize note: {}", e))?;
+
+    manager.inner.add_note(note, *cmx, *nullifier, crate::note_manager::NATIVE_ASSET, ledger_seq, *tx_hash, output_index)
+        .map_err(|e| anyhow::anyhow!("Failed to add note: {}", e))
+}
 
-    // For now, we need to receive the full note from C++
-    // In production, this would deserialize from note_bytes
-    // TODO: Implement proper note serialization/deserialization
+/// Serialize a decrypted note from a bundle action so it can be persisted
+/// and later rehydrated via [`orchard_note_manager_add_note`]
+///
+/// # Arguments
+/// * `bundle` - The Orchard bundle containing the action
+/// * `action_index` - Index of the action to decrypt (0-based)
+/// * `fvk_bytes` - Full viewing key bytes (96 bytes)
+///
+/// # Returns
+/// The note encoded via [`crate::note_manager::serialize_note`]
+pub fn orchard_note_serialize(
+    bundle: &OrchardBundle,
+    action_index: usize,
+    fvk_bytes: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    use orchard::keys::{FullViewingKey, PreparedIncomingViewingKey, Scope};
+    use zcash_note_encryption::try_note_decryption;
 
-    Err(anyhow::anyhow!("Note deserialization not yet implemented. Need to pass full Note object from scanner."))
+    let fvk_array: [u8; 96] = fvk_bytes.try_into()
+        .map_err(|_| anyhow::anyhow!("FVK must be 96 bytes"))?;
+    let fvk = FullViewingKey::from_bytes(&fvk_array)
+        .ok_or_else(|| anyhow::anyhow!("Invalid FVK"))?;
+
+    let inner_bundle = bundle.inner()
+        .ok_or_else(|| anyhow::anyhow!("Bundle is empty"))?;
+    let action = inner_bundle.actions().get(action_index)
+        .ok_or_else(|| anyhow::anyhow!("Action index out of bounds"))?;
+
+    let ivk = PreparedIncomingViewingKey::new(&fvk.to_ivk(Scope::External));
+    let domain = orchard::note_encryption::OrchardDomain::for_action(action);
+    let (note, _addr, _memo) = try_note_decryption(&domain, &ivk, action)
+        .ok_or_else(|| anyhow::anyhow!("Failed to decrypt note - not ours"))?;
+
+    Ok(crate::note_manager::serialize_note(&note).to_vec())
 }
 
 /// Mark a note as spent by its nullifier
@@ -579,14 +975,29 @@ pub fn orchard_note_manager_mark_spent(
     manager.inner.mark_spent(nullifier);
 }
 
-/// Get the total balance of unspent notes
+/// Get the total native-asset balance of unspent notes
 pub fn orchard_note_manager_get_balance(manager: &NoteManager) -> u64 {
-    manager.inner.get_balance()
+    manager.inner.get_balance(crate::note_manager::NATIVE_ASSET)
 }
 
-/// Get the count of unspent notes
+/// Get the total unspent balance of a specific asset (32-byte `AssetBase`,
+/// or [`crate::note_manager::NATIVE_ASSET`] for plain value)
+pub fn orchard_note_manager_get_asset_balance(manager: &NoteManager, asset_id: &[u8; 32]) -> u64 {
+    manager.inner.get_balance(*asset_id)
+}
+
+/// List every asset currently held, as a flat concatenation of 32-byte
+/// asset ids (i.e. `Vec<u8>` of length `32 * n`)
+pub fn orchard_note_manager_list_asset_ids(manager: &NoteManager) -> Vec<u8> {
+    manager.inner.get_balances()
+        .into_keys()
+        .flat_map(|id| id.to_vec())
+        .collect()
+}
+
+/// Get the count of unspent notes (all assets)
 pub fn orchard_note_manager_note_count(manager: &NoteManager) -> usize {
-    manager.inner.note_count()
+    manager.inner.note_count(None)
 }
 
 /// Get the current anchor (Merkle tree root)
@@ -635,17 +1046,24 @@ pub fn orchard_note_manager_decrypt_and_add_note(
     // Compute nullifier
     let nullifier = note.nullifier(&fvk).to_bytes();
 
-    // Add to manager
-    manager.inner.add_note(note, cmx, nullifier, ledger_seq, *tx_hash)
+    // Add to manager (decryption via a bare FVK only recovers native-asset notes today)
+    manager.inner.add_note(note, cmx, nullifier, crate::note_manager::NATIVE_ASSET, ledger_seq, *tx_hash, action_index as u32)
         .map_err(|e| anyhow::anyhow!("Failed to add note: {}", e))
 }
 
 /// Build a production z→z bundle with real note spending
+///
+/// `memo` is an optional 512-byte memo for the recipient's output; pass an
+/// empty slice for no memo. `sighash` is the enclosing transaction's
+/// ZIP-244 sighash, signed by every spend authorization signature and the
+/// binding signature.
 pub fn orchard_build_shielded_to_shielded_production(
     manager: &NoteManager,
     sk_bytes: &[u8],
     recipient_addr_bytes: &[u8],
     send_amount: u64,
+    memo: &[u8],
+    sighash: [u8; 32],
 ) -> anyhow::Result<Vec<u8>> {
     // Parse spending key
     let sk_array: [u8; 32] = sk_bytes.try_into()
@@ -665,12 +1083,16 @@ pub fn orchard_build_shielded_to_shielded_production(
         .into_option()
         .ok_or_else(|| anyhow::anyhow!("Invalid recipient address"))?;
 
+    let memo = parse_optional_memo(memo)?;
+
     // Build the production bundle with real spends
     crate::bundle_builder::build_shielded_to_shielded_production(
         &manager.inner,
         &sk_array,
         recipient,
         send_amount,
+        memo,
+        sighash,
     )
     .map_err(|e| anyhow::anyhow!("Failed to build production z→z bundle: {}", e))
 }
@@ -691,12 +1113,35 @@ pub fn orchard_wallet_state_reset(wallet: &mut OrchardWalletState) {
     wallet.inner.reset();
 }
 
+/// Serialize the entire wallet state (IVKs/FVKs, notes with witness
+/// positions and spent status, checkpoints, and the commitment tree) to a
+/// versioned binary blob for persistence across restarts
+pub fn orchard_wallet_state_serialize(wallet: &OrchardWalletState) -> Vec<u8> {
+    wallet.inner.serialize()
+}
+
+/// Restore a wallet state previously produced by [`orchard_wallet_state_serialize`]
+///
+/// Rejects a corrupted or truncated blob rather than returning a wallet that
+/// could produce invalid spends: every mined note's witness is re-derived
+/// from the restored tree and checked to still authenticate to its root.
+pub fn orchard_wallet_state_deserialize(bytes: &[u8]) -> anyhow::Result<Box<OrchardWalletState>> {
+    let inner = RustWalletState::deserialize(bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize wallet state: {}", e))?;
+    Ok(Box::new(OrchardWalletState { inner }))
+}
+
 /// Add an incoming viewing key to track
+///
+/// This entry point only ever receives an already-derived IVK, not a full
+/// viewing key, so it has no way to know whether the caller derived it for
+/// the external or internal scope. It assumes external (payment address),
+/// matching this function's historical behavior.
 pub fn orchard_wallet_state_add_ivk(
     wallet: &mut OrchardWalletState,
     ivk_bytes: &[u8],
 ) -> anyhow::Result<()> {
-    use orchard::keys::IncomingViewingKey;
+    use orchard::keys::{IncomingViewingKey, Scope};
 
     let ivk_array: [u8; 64] = ivk_bytes.try_into()
         .map_err(|_| anyhow::anyhow!("IVK must be 64 bytes"))?;
@@ -705,7 +1150,29 @@ pub fn orchard_wallet_state_add_ivk(
         .into_option()
         .ok_or_else(|| anyhow::anyhow!("Invalid IVK"))?;
 
-    wallet.inner.add_ivk(ivk);
+    wallet.inner.add_ivk(ivk, Scope::External);
+    Ok(())
+}
+
+/// Register a full viewing key, tracking both its external and internal
+/// (change) IVKs so notes sent to either address are recovered
+///
+/// Unlike [`orchard_wallet_state_add_ivk`], this also retains the FVK
+/// itself, which is what lets change outputs sent back to this wallet be
+/// detected during decryption.
+pub fn orchard_wallet_state_add_fvk(
+    wallet: &mut OrchardWalletState,
+    fvk_bytes: &[u8],
+) -> anyhow::Result<()> {
+    use orchard::keys::FullViewingKey;
+
+    let fvk_array: [u8; 96] = fvk_bytes.try_into()
+        .map_err(|_| anyhow::anyhow!("FVK must be 96 bytes"))?;
+
+    let fvk = FullViewingKey::from_bytes(&fvk_array)
+        .ok_or_else(|| anyhow::anyhow!("Invalid FVK"))?;
+
+    wallet.inner.add_fvk(&fvk);
     Ok(())
 }
 
@@ -732,11 +1199,59 @@ pub fn orchard_wallet_state_get_ivk_count(wallet: &OrchardWalletState) -> usize
     wallet.inner.list_ivks().len()
 }
 
-/// Get the total balance of unspent notes
+/// Get the total native-asset balance of unspent notes
 pub fn orchard_wallet_state_get_balance(wallet: &OrchardWalletState) -> u64 {
     wallet.inner.get_balance()
 }
 
+/// Get the total unspent balance of a specific asset (32-byte `AssetBase`,
+/// or [`crate::note_manager::NATIVE_ASSET`] for plain value)
+pub fn orchard_wallet_state_get_asset_balance(wallet: &OrchardWalletState, asset_id: &[u8; 32]) -> u64 {
+    wallet.inner.get_asset_balance(*asset_id, false)
+}
+
+/// List every asset currently held (unspent, mined notes only), as a flat
+/// concatenation of 32-byte asset ids (i.e. `Vec<u8>` of length `32 * n`)
+pub fn orchard_wallet_state_list_asset_ids(wallet: &OrchardWalletState) -> Vec<u8> {
+    wallet.inner.list_asset_ids()
+        .into_iter()
+        .flat_map(|id| id.to_vec())
+        .collect()
+}
+
+/// Get total native-asset balance of unspent notes meeting a confirmation threshold
+///
+/// `min_confirmations == 0` includes mempool/unconfirmed notes; otherwise a
+/// note counts once it has at least that many confirmations as of
+/// `chain_tip_seq`. See [`OrchardWalletState::get_balance_with_confirmations`].
+pub fn orchard_wallet_state_get_balance_with_confirmations(
+    wallet: &OrchardWalletState,
+    min_confirmations: u32,
+    chain_tip_seq: u32,
+) -> u64 {
+    wallet.inner.get_balance_with_confirmations(min_confirmations, chain_tip_seq)
+}
+
+/// Per-asset balance breakdown meeting a confirmation threshold, the way
+/// `z_getbalanceforaccount` reports per-pool totals
+///
+/// Returns a JSON object mapping each held asset's hex-encoded id to its
+/// balance, e.g. `{"0000...00": 5000, "0909...09": 12}`.
+pub fn orchard_wallet_state_get_balance_breakdown(
+    wallet: &OrchardWalletState,
+    min_confirmations: u32,
+    chain_tip_seq: u32,
+) -> Vec<u8> {
+    let breakdown = wallet.inner.get_balance_breakdown_with_confirmations(min_confirmations, chain_tip_seq);
+    let json = serde_json::Value::Object(
+        breakdown
+            .into_iter()
+            .map(|(asset_id, amount)| (hex::encode(asset_id), serde_json::Value::from(amount)))
+            .collect(),
+    );
+    json.to_string().into_bytes()
+}
+
 /// Get the count of notes (optionally include spent)
 pub fn orchard_wallet_state_get_note_count(
     wallet: &OrchardWalletState,
@@ -828,22 +1343,93 @@ pub fn orchard_wallet_state_try_add_note(
 /// Try to decrypt notes from an Orchard bundle
 ///
 /// This attempts to decrypt all actions in the bundle using the wallet's registered IVKs.
-/// If any notes decrypt successfully, they're added to the wallet with witnesses.
+/// If any notes decrypt successfully, they're added to the wallet with witnesses. In the
+/// same pass, any tracked note whose true nullifier (known for notes registered via
+/// [`orchard_wallet_state_add_fvk`]) is revealed by one of this bundle's actions is marked
+/// spent.
 ///
-/// Returns the number of notes successfully decrypted and added.
+/// Returns the counts of newly received and newly detected-spent notes.
 pub fn orchard_wallet_state_try_decrypt_notes(
     wallet: &mut OrchardWalletState,
     bundle: &OrchardBundle,
     ledger_seq: u32,
     tx_hash: &[u8; 32],
-) -> anyhow::Result<usize> {
+) -> anyhow::Result<ffi::WalletScanResult> {
     // Get the inner bundle
     let inner_bundle = bundle.inner()
         .ok_or_else(|| anyhow::anyhow!("Bundle is empty"))?;
 
     // Try to decrypt notes from the bundle
-    wallet.inner.try_decrypt_notes_from_bundle(inner_bundle, *tx_hash, ledger_seq)
-        .map_err(|e| anyhow::anyhow!("Failed to decrypt notes: {}", e))
+    let result = wallet.inner.try_decrypt_notes_from_bundle(inner_bundle, *tx_hash, ledger_seq)
+        .map_err(|e| anyhow::anyhow!("Failed to decrypt notes: {}", e))?;
+
+    Ok(ffi::WalletScanResult {
+        notes_received: result.notes_received,
+        notes_spent: result.notes_spent,
+    })
+}
+
+/// Scan many bundles for this wallet's notes in a single batched decryption pass
+///
+/// Queue bundles with [`orchard_scan_batch_add`] first; this consumes the batch.
+/// Much faster than calling [`orchard_wallet_state_try_decrypt_notes`] once per
+/// bundle when scanning a whole ledger range, since the expensive note-encryption
+/// key agreement is vectorized across every action at once.
+///
+/// Returns the total number of notes successfully decrypted and added.
+pub fn orchard_wallet_state_batch_scan(
+    wallet: &mut OrchardWalletState,
+    batch: Box<OrchardScanBatch>,
+) -> anyhow::Result<usize> {
+    let entries: Vec<(&orchard::Bundle<orchard::bundle::Authorized, zcash_protocol::value::ZatBalance>, u32, [u8; 32])> =
+        batch.entries
+            .iter()
+            .filter_map(|(bundle, ledger_seq, tx_hash)| {
+                bundle.inner().map(|inner| (inner, *ledger_seq, *tx_hash))
+            })
+            .collect();
+
+    wallet.inner.batch_decrypt_notes(&entries)
+        .map_err(|e| anyhow::anyhow!("Failed to batch scan notes: {}", e))
+}
+
+/// Get the Merkle authentication path a spend of this note would need
+///
+/// Returns `position: u64` (8 bytes, little-endian) followed by the 32
+/// sibling hashes of the auth path (32 bytes each, root-ward), matching the
+/// layout [`orchard::tree::MerklePath::from_parts`] expects - 1032 bytes total.
+/// Only produced for a note that's been mined (has a tree position).
+pub fn orchard_wallet_state_get_witness(
+    wallet: &OrchardWalletState,
+    cmx: &[u8; 32],
+) -> anyhow::Result<Vec<u8>> {
+    let note = wallet.inner.get_note(cmx)
+        .ok_or_else(|| anyhow::anyhow!("Note not found"))?;
+
+    let (position, auth_path) = wallet.inner.get_witness_parts(note)
+        .map_err(|e| anyhow::anyhow!("Failed to get witness: {}", e))?;
+
+    let mut bytes = Vec::with_capacity(8 + 32 * 32);
+    bytes.extend_from_slice(&(position as u64).to_le_bytes());
+    for hash in auth_path.iter() {
+        bytes.extend_from_slice(&hash.to_bytes());
+    }
+
+    Ok(bytes)
+}
+
+/// Get the anchor a note's current witness (from [`orchard_wallet_state_get_witness`]) authenticates to
+pub fn orchard_wallet_state_witness_anchor(
+    wallet: &OrchardWalletState,
+    cmx: &[u8; 32],
+) -> anyhow::Result<Vec<u8>> {
+    let note = wallet.inner.get_note(cmx)
+        .ok_or_else(|| anyhow::anyhow!("Note not found"))?;
+
+    let anchor = wallet.inner.get_note_anchor(note)
+        .map_err(|e| anyhow::anyhow!("Failed to get witness anchor: {}", e))?;
+
+    Ok(anchor.to_bytes().to_vec())
 }
 
 /// Mark a note as spent by nullifier
@@ -867,6 +1453,20 @@ pub fn orchard_wallet_state_last_checkpoint(wallet: &OrchardWalletState) -> u32
     wallet.inner.last_checkpoint().unwrap_or(0)
 }
 
+/// Roll the wallet back after a reorg, to the most recent checkpoint at or
+/// before `target_ledger_seq`
+///
+/// Undoes notes received and spends observed only in rewound ledgers, and
+/// regenerates witnesses for surviving notes from the restored tree frontier.
+/// Returns the ledger_seq actually rewound to.
+pub fn orchard_wallet_state_rewind(
+    wallet: &mut OrchardWalletState,
+    target_ledger_seq: u32,
+) -> anyhow::Result<u32> {
+    wallet.inner.rewind_to_or_before(target_ledger_seq)
+        .map_err(|e| anyhow::anyhow!("Failed to rewind wallet: {}", e))
+}
+
 // ============================================================================
 // Key Derivation Utilities
 // ============================================================================
@@ -878,10 +1478,13 @@ pub fn orchard_wallet_state_last_checkpoint(wallet: &OrchardWalletState) -> u32
 ///
 /// # Arguments
 /// * `fvk_bytes` - Full viewing key bytes (96 bytes)
+/// * `scope` - `0` for the external (payment) address, `1` for the internal
+///   (change) address - a wallet that wants to recover its own change outputs
+///   needs both
 ///
 /// # Returns
-/// Incoming viewing key bytes (64 bytes) for the External scope
-pub fn orchard_derive_ivk_from_fvk(fvk_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+/// Incoming viewing key bytes (64 bytes) for the requested scope
+pub fn orchard_derive_ivk_from_fvk(fvk_bytes: &[u8], scope: u8) -> anyhow::Result<Vec<u8>> {
     use orchard::keys::{FullViewingKey, Scope};
 
     let fvk_array: [u8; 96] = fvk_bytes.try_into()
@@ -890,8 +1493,12 @@ pub fn orchard_derive_ivk_from_fvk(fvk_bytes: &[u8]) -> anyhow::Result<Vec<u8>>
     let fvk = FullViewingKey::from_bytes(&fvk_array)
         .ok_or_else(|| anyhow::anyhow!("Invalid FVK"))?;
 
-    // Derive the external IVK (used for receiving payments)
-    let ivk = fvk.to_ivk(Scope::External);
+    let scope = match scope {
+        0 => Scope::External,
+        1 => Scope::Internal,
+        other => return Err(anyhow::anyhow!("Unknown scope selector: {} (expected 0=external or 1=internal)", other)),
+    };
+    let ivk = fvk.to_ivk(scope);
 
     Ok(ivk.to_bytes().to_vec())
 }
@@ -909,6 +1516,11 @@ pub fn orchard_derive_ivk_from_fvk(fvk_bytes: &[u8]) -> anyhow::Result<Vec<u8>>
 /// * `sk_bytes` - Spending key (32 bytes) - SECURITY: Do not store!
 /// * `recipient_addr_bytes` - Recipient Orchard address (43 bytes)
 /// * `send_amount` - Amount to send in drops
+/// * `memo` - Optional 512-byte memo for the recipient's output; pass an
+///   empty slice for no memo
+/// * `sighash` - The transaction's BLAKE2b signature hash (ZIP-244), signed by
+///   every spend authorization signature and the binding signature; the
+///   bundle will only validate against this exact sighash
 ///
 /// # Returns
 /// Serialized Orchard bundle bytes
@@ -922,6 +1534,8 @@ pub fn orchard_wallet_build_z_to_z(
     recipient_addr_bytes: &[u8],
     send_amount: u64,
     fee: u64,
+    memo: &[u8],
+    sighash: [u8; 32],
 ) -> anyhow::Result<Vec<u8>> {
     use orchard::Address;
 
@@ -936,6 +1550,8 @@ pub fn orchard_wallet_build_z_to_z(
     let recipient = Option::from(Address::from_raw_address_bytes(&addr_array))
         .ok_or_else(|| anyhow::anyhow!("Invalid Orchard address"))?;
 
+    let memo = parse_optional_memo(memo)?;
+
     // Build the bundle using wallet state
     crate::bundle_builder::build_shielded_to_shielded_from_wallet(
         &wallet.inner,
@@ -943,10 +1559,109 @@ pub fn orchard_wallet_build_z_to_z(
         recipient,
         send_amount,
         fee,
+        memo,
+        sighash,
     )
     .map_err(|e| anyhow::anyhow!("Failed to build z→z bundle: {}", e))
 }
 
+/// Byte length of one `outputs` entry for [`orchard_wallet_build_z_to_z_multi`]:
+/// a 43-byte recipient address, an 8-byte little-endian amount, and a 512-byte memo.
+const MULTI_OUTPUT_ENTRY_LEN: usize = 43 + 8 + 512;
+
+/// Build a production z→z transaction paying multiple recipients, each with
+/// its own memo
+///
+/// `outputs` is a flat concatenation of `(recipient_addr[43] || amount_le[8]
+/// || memo[512])` entries, one per recipient - cxx can't pass a slice of
+/// tuples across the FFI boundary, so this mirrors the flat-concatenation
+/// convention used elsewhere in this bridge (e.g. `rk_list` in
+/// [`orchard_unauthorized_bundle_apply_signatures`]).
+///
+/// `bundle_type` selects the padding/action-count policy:
+/// - `0` - `Transactional` with both spends and outputs enabled (the normal case)
+/// - `1` - `Coinbase` (outputs-only; spends disabled)
+/// - `2` - `Transactional` with spends disabled (outputs-only, but still padded
+///   as a regular transaction rather than a coinbase one)
+/// - `3` - `Transactional` with outputs disabled; always rejected, since the
+///   builder needs at least one output and refuses a type with outputs disabled
+///
+/// # Arguments
+/// * `wallet` - Wallet state with tracked notes and commitment tree
+/// * `sk_bytes` - Spending key (32 bytes) - SECURITY: Do not store!
+/// * `outputs` - Recipients to pay, see above
+/// * `fee` - Fee in drops, covered alongside `outputs`' total when selecting notes to spend
+/// * `bundle_type` - Padding/action-count policy selector, see above
+/// * `sighash` - The transaction's BLAKE2b signature hash (ZIP-244), signed by
+///   every spend authorization signature and the binding signature; the
+///   bundle will only validate against this exact sighash
+///
+/// # Returns
+/// Serialized Orchard bundle bytes
+///
+/// # Note
+/// This is PRODUCTION-READY and will create valid on-chain transactions.
+/// Proof generation takes ~5-10 seconds.
+pub fn orchard_wallet_build_z_to_z_multi(
+    wallet: &OrchardWalletState,
+    sk_bytes: &[u8],
+    outputs: &[u8],
+    fee: u64,
+    bundle_type: u8,
+    sighash: [u8; 32],
+) -> anyhow::Result<Vec<u8>> {
+    use orchard::Address;
+
+    let sk_array: [u8; 32] = sk_bytes.try_into()
+        .map_err(|_| anyhow::anyhow!("Spending key must be 32 bytes"))?;
+
+    if outputs.len() % MULTI_OUTPUT_ENTRY_LEN != 0 {
+        return Err(anyhow::anyhow!(
+            "outputs must be a concatenation of {}-byte (address + amount + memo) entries, got {} bytes",
+            MULTI_OUTPUT_ENTRY_LEN,
+            outputs.len(),
+        ));
+    }
+
+    let mut parsed_outputs = Vec::with_capacity(outputs.len() / MULTI_OUTPUT_ENTRY_LEN);
+    for entry in outputs.chunks_exact(MULTI_OUTPUT_ENTRY_LEN) {
+        let addr_array: [u8; 43] = entry[0..43].try_into().unwrap();
+        let recipient = Address::from_raw_address_bytes(&addr_array)
+            .into_option()
+            .ok_or_else(|| anyhow::anyhow!("Invalid recipient address"))?;
+        let amount = u64::from_le_bytes(entry[43..51].try_into().unwrap());
+        let memo: [u8; 512] = entry[51..563].try_into().unwrap();
+        parsed_outputs.push((recipient, amount, memo));
+    }
+
+    let bundle_type = match bundle_type {
+        0 => orchard::builder::BundleType::Transactional {
+            flags: orchard::bundle::Flags::ENABLED,
+            bundle_required: true,
+        },
+        1 => orchard::builder::BundleType::Coinbase,
+        2 => orchard::builder::BundleType::Transactional {
+            flags: orchard::bundle::Flags::from_parts(false, true),
+            bundle_required: true,
+        },
+        3 => orchard::builder::BundleType::Transactional {
+            flags: orchard::bundle::Flags::from_parts(true, false),
+            bundle_required: true,
+        },
+        other => return Err(anyhow::anyhow!("Unknown bundle_type selector: {}", other)),
+    };
+
+    crate::bundle_builder::build_shielded_to_shielded_multi_from_wallet(
+        &wallet.inner,
+        &sk_array,
+        &parsed_outputs,
+        fee,
+        bundle_type,
+        sighash,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to build multi-output z→z bundle: {}", e))
+}
+
 /// Build a production z→t transaction using OrchardWalletState
 ///
 /// This function:
@@ -960,6 +1675,13 @@ pub fn orchard_wallet_build_z_to_z(
 /// * `wallet` - Wallet state with tracked notes and commitment tree
 /// * `sk_bytes` - Spending key (32 bytes) - SECURITY: Do not store!
 /// * `unshield_amount` - Amount to transfer to transparent pool in drops
+/// * `fee` - Accepted for API symmetry with the other wallet builders, but
+///   [`build_shielded_to_transparent`](crate::bundle_builder::build_shielded_to_transparent)
+///   has no fee concept of its own today - the caller must fold any fee into
+///   `unshield_amount` up front.
+/// * `sighash` - The transaction's BLAKE2b signature hash (ZIP-244), signed by
+///   every spend authorization signature and the binding signature; the
+///   bundle will only validate against this exact sighash
 ///
 /// # Returns
 /// Serialized Orchard bundle bytes with positive value balance
@@ -971,7 +1693,8 @@ pub fn orchard_wallet_build_z_to_t(
     wallet: &OrchardWalletState,
     sk_bytes: &[u8],
     unshield_amount: u64,
-    fee: u64,
+    _fee: u64,
+    sighash: [u8; 32],
 ) -> anyhow::Result<Vec<u8>> {
     // Parse spending key
     let sk_array: [u8; 32] = sk_bytes.try_into()
@@ -982,7 +1705,252 @@ pub fn orchard_wallet_build_z_to_t(
         &wallet.inner,
         &sk_array,
         unshield_amount,
-        fee,
+        sighash,
     )
     .map_err(|e| anyhow::anyhow!("Failed to build z→t bundle: {}", e))
 }
+
+// ============================================================================
+// Two-Phase Bundle Construction for External/Hardware Signers
+// ============================================================================
+
+/// Build and prove a z→z bundle without a spending key, ready for an
+/// external signer to authorize
+///
+/// Spend authorization in Orchard only needs the full viewing key to select
+/// notes and build spend proofs - the spending key is only needed to sign.
+/// This does everything up to that point, so a hardware wallet or HSM can
+/// hold the one thing that actually matters: the spending key itself.
+///
+/// # Arguments
+/// * `wallet` - Wallet state with tracked notes and commitment tree
+/// * `fvk_bytes` - Full viewing key bytes (96 bytes)
+/// * `recipient_addr_bytes` - Recipient Orchard address (43 bytes)
+/// * `send_amount` - Amount to send in drops
+/// * `fee` - Fee in drops, covered by an internal (change) output alongside `send_amount`
+/// * `memo` - Optional 512-byte memo for the recipient's output; pass an
+///   empty slice for no memo. The change output (if any) always carries an
+///   empty memo.
+/// * `sighash` - The transaction's BLAKE2b signature hash (ZIP-244), computed
+///   by the caller once the enclosing transaction (this bundle plus every
+///   other input/output) is fully assembled. Spend-authorization signatures
+///   sign `alpha`-randomized keys over this hash, and the binding signature
+///   commits the value balance to it, so a wrong or placeholder hash here
+///   produces a bundle that can never validate on-chain - it must be the
+///   real one, not a stand-in computed before assembly finishes.
+///
+/// # Returns
+/// An opaque handle for [`orchard_bundle_sighash`] and [`orchard_apply_signatures`]
+pub fn orchard_build_unauthorized(
+    wallet: &OrchardWalletState,
+    fvk_bytes: &[u8],
+    recipient_addr_bytes: &[u8],
+    send_amount: u64,
+    fee: u64,
+    memo: &[u8],
+    sighash: [u8; 32],
+) -> anyhow::Result<Box<UnauthorizedOrchardBundle>> {
+    use orchard::builder::{Builder, BundleType};
+    use orchard::keys::{FullViewingKey, Scope};
+    use orchard::value::NoteValue;
+    use orchard::Address;
+    use rand::rngs::OsRng;
+
+    let fvk_array: [u8; 96] = fvk_bytes.try_into()
+        .map_err(|_| anyhow::anyhow!("Full viewing key must be 96 bytes"))?;
+    let fvk = FullViewingKey::from_bytes(&fvk_array)
+        .ok_or_else(|| anyhow::anyhow!("Invalid full viewing key"))?;
+
+    let recipient_memo = parse_optional_memo(memo)?.unwrap_or([0u8; 512]);
+
+    let addr_array: [u8; 43] = recipient_addr_bytes.try_into()
+        .map_err(|_| anyhow::anyhow!("Recipient address must be 43 bytes"))?;
+    let recipient = Address::from_raw_address_bytes(&addr_array)
+        .into_option()
+        .ok_or_else(|| anyhow::anyhow!("Invalid recipient address"))?;
+
+    let anchor = wallet.inner.get_anchor()
+        .map_err(|e| anyhow::anyhow!("Failed to get anchor: {}", e))?;
+
+    let total_amount = send_amount.checked_add(fee)
+        .ok_or_else(|| anyhow::anyhow!("Amount overflow"))?;
+    let selected_notes = wallet.inner.select_notes(total_amount, Some(&fvk))
+        .map_err(|e| anyhow::anyhow!("Failed to select notes: {}", e))?;
+
+    let mut total_input = 0u64;
+    for note in &selected_notes {
+        total_input = total_input.checked_add(note.amount)
+            .ok_or_else(|| anyhow::anyhow!("Amount overflow"))?;
+    }
+    let change_amount = total_input.checked_sub(total_amount)
+        .ok_or_else(|| anyhow::anyhow!("Insufficient balance"))?;
+
+    let mut builder = Builder::new(
+        BundleType::Transactional {
+            flags: orchard::bundle::Flags::ENABLED,
+            bundle_required: true,
+        },
+        anchor,
+    );
+
+    for note in &selected_notes {
+        let merkle_path = wallet.inner.get_merkle_path(note)
+            .map_err(|e| anyhow::anyhow!("Failed to get merkle path: {}", e))?;
+        builder.add_spend(fvk.clone(), note.note.clone(), merkle_path)
+            .map_err(|e| anyhow::anyhow!("Failed to add spend: {:?}", e))?;
+    }
+
+    // Derive our own outgoing viewing key so outputs we create can later be
+    // recovered from the chain with `orchard_try_recover_output`, without
+    // needing to have been the recipient.
+    let ovk = fvk.to_ovk(Scope::External);
+
+    builder.add_output(Some(ovk.clone()), recipient, NoteValue::from_raw(send_amount), recipient_memo)
+        .map_err(|e| anyhow::anyhow!("Failed to add recipient output: {:?}", e))?;
+
+    if change_amount > 0 {
+        let change_address = fvk.address_at(0u32, Scope::Internal);
+        builder.add_output(Some(ovk), change_address, NoteValue::from_raw(change_amount), [0u8; 512])
+            .map_err(|e| anyhow::anyhow!("Failed to add change output: {:?}", e))?;
+    }
+
+    let mut rng = OsRng;
+    let (unproven, _metadata) = builder.build(&mut rng)
+        .map_err(|e| anyhow::anyhow!("Failed to build bundle: {:?}", e))?
+        .ok_or_else(|| anyhow::anyhow!("Builder produced empty bundle"))?;
+
+    let pk = crate::bundle_builder::orchard_proving_key();
+    let proven = unproven.create_proof(pk, &mut rng)
+        .map_err(|e| anyhow::anyhow!("Failed to create proof: {:?}", e))?;
+
+    // `sighash` is the caller's real ZIP-244 transaction hash (see the
+    // argument doc above) - the proof doesn't depend on it; `prepare` signs
+    // the binding signature over it here, and the spend authorization
+    // signatures applied in the next phase sign it too.
+    let bundle = proven.prepare(rng, sighash);
+
+    Ok(Box::new(UnauthorizedOrchardBundle { bundle, sighash }))
+}
+
+/// The sighash an external signer must produce a RedPallas signature over
+/// for each spend in `bundle`, plus the binding signature
+pub fn orchard_bundle_sighash(bundle: &UnauthorizedOrchardBundle) -> [u8; 32] {
+    bundle.sighash
+}
+
+/// Attach externally produced spend authorization signatures and finalize
+/// the bundle
+///
+/// Each signature is the external signer's RedPallas signature over the
+/// sighash from [`orchard_bundle_sighash`], produced with `ask.randomize(alpha)`
+/// for that spend - `orchard::Bundle::append_signatures` matches each one to
+/// its action by verifying it against that action's randomized verification
+/// key, so the caller doesn't need to track which signature belongs to
+/// which spend or ever learn `alpha`. The binding signature is derived
+/// internally from the value balance, as it would be for a locally-signed
+/// bundle.
+///
+/// # Arguments
+/// * `bundle` - Handle from [`orchard_build_unauthorized`]
+/// * `signatures` - One or more 64-byte RedPallas `Signature<SpendAuth>` values, concatenated
+///
+/// # Returns
+/// The finalized, fully authorized bundle, serialized as ZIP-225 bytes
+pub fn orchard_apply_signatures(
+    bundle: Box<UnauthorizedOrchardBundle>,
+    signatures: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    use orchard::primitives::redpallas::{Signature, SpendAuth};
+
+    if signatures.len() % 64 != 0 {
+        return Err(anyhow::anyhow!(
+            "Signatures must be a concatenation of 64-byte values, got {} bytes",
+            signatures.len()
+        ));
+    }
+
+    let parsed_signatures: Vec<Signature<SpendAuth>> = signatures
+        .chunks_exact(64)
+        .map(|chunk| {
+            let sig_array: [u8; 64] = chunk.try_into().unwrap();
+            Signature::from(sig_array)
+        })
+        .collect();
+
+    let authorized = bundle.bundle
+        .append_signatures(&parsed_signatures)
+        .map_err(|e| anyhow::anyhow!("Failed to attach signatures: {:?}", e))?
+        .finalize()
+        .map_err(|e| anyhow::anyhow!("Failed to finalize bundle: {:?}", e))?;
+
+    let mut bundle_bytes = Vec::new();
+    zcash_primitives::transaction::components::orchard::write_v5_bundle(
+        Some(&authorized),
+        &mut bundle_bytes,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to serialize bundle: {}", e))?;
+
+    Ok(bundle_bytes)
+}
+
+/// Same as [`orchard_build_unauthorized`], named to match the `_z_to_z`
+/// production builders
+pub fn orchard_wallet_build_unauthorized_z_to_z(
+    wallet: &OrchardWalletState,
+    fvk_bytes: &[u8],
+    recipient_addr_bytes: &[u8],
+    send_amount: u64,
+    fee: u64,
+    memo: &[u8],
+    sighash: [u8; 32],
+) -> anyhow::Result<Box<UnauthorizedOrchardBundle>> {
+    orchard_build_unauthorized(wallet, fvk_bytes, recipient_addr_bytes, send_amount, fee, memo, sighash)
+}
+
+/// Same as [`orchard_bundle_sighash`], named to match [`orchard_wallet_build_unauthorized_z_to_z`]
+pub fn orchard_unauthorized_bundle_sighash(bundle: &UnauthorizedOrchardBundle) -> [u8; 32] {
+    orchard_bundle_sighash(bundle)
+}
+
+/// Same as [`orchard_apply_signatures`], but first cross-checks `rk_list`
+/// (the external signer's claimed per-action randomized verification keys,
+/// 32 bytes each, same order as the bundle's actions) against the bundle's
+/// actual ones.
+///
+/// `append_signatures` already rejects a signature that doesn't verify
+/// against its action's `rk`, so this check doesn't change which bundles can
+/// be finalized - it exists to give a hardware signer a clear, specific error
+/// ("your rk_list is in the wrong order") instead of a generic signature
+/// verification failure when it's mismatched the `rk`s it signed against.
+pub fn orchard_unauthorized_bundle_apply_signatures(
+    bundle: Box<UnauthorizedOrchardBundle>,
+    rk_list: &[u8],
+    signatures: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    if rk_list.len() % 32 != 0 {
+        return Err(anyhow::anyhow!(
+            "rk_list must be a concatenation of 32-byte values, got {} bytes",
+            rk_list.len()
+        ));
+    }
+
+    let actions = bundle.bundle.actions();
+    if rk_list.len() / 32 != actions.len() {
+        return Err(anyhow::anyhow!(
+            "Expected {} verification keys (one per action), got {}",
+            actions.len(),
+            rk_list.len() / 32
+        ));
+    }
+
+    for (action, rk_bytes) in actions.iter().zip(rk_list.chunks_exact(32)) {
+        if action.rk().to_bytes() != rk_bytes {
+            return Err(anyhow::anyhow!(
+                "rk_list entry does not match this bundle's action verification key - \
+                 wrong order, or signed against the wrong bundle"
+            ));
+        }
+    }
+
+    orchard_apply_signatures(bundle, signatures)
+}
@@ -6,7 +6,12 @@
 
 // Use the real implementation for Phase 3
 mod bundle_real;
-pub use bundle_real::OrchardBundle;
+pub use bundle_real::{OrchardBundle, BundleConsensusError, BundleInspection, ActionInspection, Action, verifying_key};
+
+// Property-test generators for `OrchardBundle`, gated the same way the
+// upstream `orchard` crate gates its own `test-dependencies` feature.
+#[cfg(feature = "test-dependencies")]
+pub use bundle_real::testing;
 
 // Bundle builder for testing and wallet functionality
 // Always available for FFI test functions
@@ -18,6 +23,17 @@ pub mod note_manager;
 // Wallet state for server-side note tracking (Zcash-style)
 pub mod wallet_state;
 
+// Sharded, pruning-capable commitment tree storage used by wallet_state
+pub mod shard_store;
+
+// Amortized multi-bundle proof/signature verification
+pub mod batch;
+
+// Process-global cache of bundle validation results, keyed by authorizing
+// data + sighash
+mod validation_cache;
+pub use validation_cache::{cache_stats, clear_cache};
+
 // Keep old stub for reference (not used)
 #[allow(dead_code)]
 mod bundle_stub;
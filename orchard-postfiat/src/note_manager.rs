@@ -5,49 +5,174 @@
 //! - Maintaining the Merkle commitment tree
 //! - Generating witness paths for spending
 //! - Selecting notes for transactions
+//! - Persisting and restoring state across restarts
+//! - Looking notes up by commitment, nullifier, or (tx, output index)
 
 use orchard::{
-    note::Note,
+    note::{Note, RandomSeed, Rho},
     tree::{MerkleHashOrchard, MerklePath},
-    Anchor,
-};
-use incrementalmerkletree::{
-    frontier::CommitmentTree,
-    witness::IncrementalWitness,
-    Hashable,
-    Position,
+    value::NoteValue,
+    Address, Anchor,
 };
+use incrementalmerkletree::{Hashable, Position};
+use bridgetree::BridgeTree;
+use rand::seq::SliceRandom;
 use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+/// Bound on the number of branches explored by [`branch_and_bound`] before
+/// giving up and falling back to single random draw.
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Current version of the [`NoteManager`] on-disk format, written as the
+/// first byte of every serialized state (see [`NoteManager::write`]).
+const SERIALIZATION_VERSION: u8 = 2;
+
+/// Identifier for the asset a note carries (Orchard Shielded Asset `AssetBase`).
+///
+/// This crate does not yet build against a ZSA-enabled `orchard`, so the asset
+/// base itself stays opaque here: callers that decode ZSA notes are
+/// responsible for extracting the 32-byte `AssetBase` and passing it in.
+pub type AssetId = [u8; 32];
+
+/// Asset identifier reserved for native (non-ZSA) value.
+pub const NATIVE_ASSET: AssetId = [0u8; 32];
+
+/// Canonical byte length of a note serialized by [`serialize_note`]:
+/// recipient (43) + value (8) + rho (32) + rseed (32).
+pub const SERIALIZED_NOTE_LEN: usize = 43 + 8 + 32 + 32;
+
+/// Serialize a `Note` on its own, independent of any [`SpendableNote`]
+/// bookkeeping, so it can cross the FFI boundary and be written to disk by a
+/// C++ scanner.
+///
+/// Layout: recipient address (43 bytes) ‖ value (8 bytes LE) ‖ rho (32 bytes)
+/// ‖ rseed (32 bytes). This is the same recipient/rho/rseed encoding
+/// [`NoteManager::write`] uses, with the value folded in so the bytes are
+/// self-contained rather than relying on an accompanying `SpendableNote`.
+pub fn serialize_note(note: &Note) -> [u8; SERIALIZED_NOTE_LEN] {
+    let mut bytes = [0u8; SERIALIZED_NOTE_LEN];
+    bytes[0..43].copy_from_slice(&note.recipient().to_raw_address_bytes());
+    bytes[43..51].copy_from_slice(&note.value().inner().to_le_bytes());
+    bytes[51..83].copy_from_slice(&note.rho().to_bytes());
+    bytes[83..115].copy_from_slice(note.rseed().as_bytes());
+    bytes
+}
+
+/// Reconstruct a `Note` from bytes produced by [`serialize_note`], then
+/// validate it against the commitment the caller claims it belongs to.
+///
+/// Returns an error if the bytes don't decode to a valid note, or if the
+/// reconstructed note's commitment doesn't match `expected_cmx` - the latter
+/// check is what makes it safe to trust a note handed in across the FFI
+/// boundary instead of recovered via trial decryption.
+pub fn deserialize_note(bytes: &[u8], expected_cmx: &[u8; 32]) -> Result<Note, String> {
+    if bytes.len() != SERIALIZED_NOTE_LEN {
+        return Err(format!(
+            "Expected {} bytes for a serialized note, got {}",
+            SERIALIZED_NOTE_LEN,
+            bytes.len()
+        ));
+    }
+
+    let recipient = Address::from_raw_address_bytes(bytes[0..43].try_into().unwrap())
+        .into_option()
+        .ok_or_else(|| "Invalid note recipient address".to_string())?;
+    let value = NoteValue::from_raw(u64::from_le_bytes(bytes[43..51].try_into().unwrap()));
+    let rho = Rho::from_bytes(bytes[51..83].try_into().unwrap())
+        .into_option()
+        .ok_or_else(|| "Invalid note rho".to_string())?;
+    let rseed = RandomSeed::from_bytes(bytes[83..115].try_into().unwrap(), &rho)
+        .into_option()
+        .ok_or_else(|| "Invalid note random seed".to_string())?;
+
+    let note = Note::from_parts(recipient, value, rho, rseed)
+        .into_option()
+        .ok_or_else(|| "Invalid note parts".to_string())?;
+
+    let cmx = orchard::note::ExtractedNoteCommitment::from(note.commitment()).to_bytes();
+    if &cmx != expected_cmx {
+        return Err("Note commitment does not match expected cmx".to_string());
+    }
+
+    Ok(note)
+}
+
+/// Identifies a note by the transaction that created it and its output
+/// position within that transaction's action list
+///
+/// This lets callers address a specific output positionally (e.g. to
+/// reconcile against ledger data that references outputs by index) rather
+/// than only by its commitment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NoteId {
+    pub tx_hash: [u8; 32],
+    pub output_index: u32,
+}
 
 /// A note with all the information needed to spend it
 #[derive(Clone)]
 pub struct SpendableNote {
     /// The full Orchard note object
     pub note: Note,
-    /// Position in the commitment tree
+    /// Position in the commitment tree (witness is derived on demand from this)
     pub position: Position,
-    /// Witness for generating merkle paths
-    pub witness: IncrementalWitness<MerkleHashOrchard, 32>,
     /// The note commitment (cmx)
     pub cmx: [u8; 32],
     /// The nullifier for this note
     pub nullifier: [u8; 32],
+    /// Asset this note carries (`NATIVE_ASSET` for plain ZEC/XRP value)
+    pub asset_id: AssetId,
     /// Amount in drops
     pub amount: u64,
     /// Ledger sequence where note was received
     pub ledger_seq: u32,
     /// Transaction hash
     pub tx_hash: [u8; 32],
+    /// Index of this note's action within its transaction
+    pub output_index: u32,
+}
+
+impl SpendableNote {
+    /// This note's [`NoteId`]
+    pub fn note_id(&self) -> NoteId {
+        NoteId {
+            tx_hash: self.tx_hash,
+            output_index: self.output_index,
+        }
+    }
+}
+
+/// Maximum number of reorg checkpoints retained before the oldest is discarded
+const MAX_CHECKPOINTS: usize = 100;
+
+/// Bookkeeping for one checkpoint: which notes/spends existed as of that ledger
+///
+/// The commitment tree keeps its own checkpoint stack (bounded to the same
+/// depth via `BridgeTree::new(MAX_CHECKPOINTS)`); this tracks the
+/// note-manager-level state that the tree doesn't know about.
+#[derive(Clone)]
+struct CheckpointState {
+    ledger_seq: u32,
+    notes: HashMap<[u8; 32], SpendableNote>,
+    spent_notes: std::collections::HashSet<[u8; 32]>,
+    nullifier_index: HashMap<[u8; 32], [u8; 32]>,
 }
 
 /// Manages Orchard notes and the commitment tree
 pub struct NoteManager {
     /// All unspent notes owned by this viewing key
     notes: HashMap<[u8; 32], SpendableNote>, // keyed by cmx
-    /// The commitment tree (tracks all note commitments)
-    tree: CommitmentTree<MerkleHashOrchard, 32>,
+    /// Shared commitment tree: every observed commitment is appended, but
+    /// only our own notes are `mark()`ed, so witnesses stay cheap to derive
+    /// on demand and automatically track the latest anchor.
+    tree: BridgeTree<MerkleHashOrchard, u32, 32>,
     /// Spent note commitments (for faster lookup)
     spent_notes: std::collections::HashSet<[u8; 32]>,
+    /// Nullifier -> cmx, so `mark_spent` doesn't need to scan every note
+    nullifier_index: HashMap<[u8; 32], [u8; 32]>,
+    /// Note-manager-level snapshots keyed by ledger sequence, most recent last
+    checkpoints: Vec<CheckpointState>,
 }
 
 impl NoteManager {
@@ -55,94 +180,165 @@ impl NoteManager {
     pub fn new() -> Self {
         Self {
             notes: HashMap::new(),
-            tree: CommitmentTree::empty(),
+            tree: BridgeTree::new(MAX_CHECKPOINTS),
             spent_notes: std::collections::HashSet::new(),
+            nullifier_index: HashMap::new(),
+            checkpoints: Vec::new(),
         }
     }
 
+    /// Observe a commitment that does not belong to us
+    ///
+    /// Must be called for every commitment in the ledger, in order, so the
+    /// tree (and therefore every stored witness) stays consistent with the
+    /// chain. Commitments added via [`NoteManager::add_note`] already call
+    /// this internally.
+    pub fn append_commitment(&mut self, cmx: [u8; 32]) -> Result<(), String> {
+        let cmx_hash = MerkleHashOrchard::from_bytes(&cmx)
+            .into_option()
+            .ok_or_else(|| "Invalid commitment bytes".to_string())?;
+
+        self.tree.append(cmx_hash)
+            .then_some(())
+            .ok_or_else(|| "Failed to add to tree (tree full)".to_string())
+    }
+
+    /// Record a recoverable checkpoint of the current tree/note state
+    ///
+    /// Call this once per ledger after scanning it. Only the last
+    /// `MAX_CHECKPOINTS` checkpoints are retained; older ones are discarded.
+    pub fn checkpoint(&mut self, ledger_seq: u32) {
+        self.tree.checkpoint(ledger_seq);
+
+        self.checkpoints.push(CheckpointState {
+            ledger_seq,
+            notes: self.notes.clone(),
+            spent_notes: self.spent_notes.clone(),
+            nullifier_index: self.nullifier_index.clone(),
+        });
+
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+    }
+
+    /// Roll the tree and all witnesses back to a previously recorded checkpoint
+    ///
+    /// This undoes everything observed after `ledger_seq`: notes received
+    /// later are removed, and nullifiers only spent later are un-marked.
+    /// Used to recover from a chain reorganization without a full rescan.
+    pub fn rewind_to(&mut self, ledger_seq: u32) -> Result<(), String> {
+        let idx = self.checkpoints.iter()
+            .position(|c| c.ledger_seq == ledger_seq)
+            .ok_or_else(|| format!("No checkpoint recorded at ledger {}", ledger_seq))?;
+
+        // Drop tree checkpoints newer than the target, one at a time
+        while self.checkpoints.len() > idx + 1 {
+            self.tree.rewind()
+                .then_some(())
+                .ok_or_else(|| "Failed to rewind commitment tree".to_string())?;
+            self.checkpoints.pop();
+        }
+
+        let state = self.checkpoints[idx].clone();
+        self.notes = state.notes;
+        self.spent_notes = state.spent_notes;
+        self.nullifier_index = state.nullifier_index;
+        Ok(())
+    }
+
     /// Add a received note to the manager
     ///
     /// This should be called when scanning the ledger and discovering a note
-    /// that belongs to our viewing key.
+    /// that belongs to our viewing key. `asset_id` should be `NATIVE_ASSET`
+    /// for plain value, or the note's `AssetBase` for an issued ZSA asset.
+    /// `output_index` is the note's action index within `tx_hash`, used to
+    /// build its [`NoteId`].
     pub fn add_note(
         &mut self,
         note: Note,
         cmx: [u8; 32],
         nullifier: [u8; 32],
+        asset_id: AssetId,
         ledger_seq: u32,
         tx_hash: [u8; 32],
+        output_index: u32,
     ) -> Result<(), String> {
         let amount = note.value().inner();
 
-        // Add commitment to tree
-        let cmx_hash = MerkleHashOrchard::from_bytes(&cmx)
-            .into_option()
-            .ok_or_else(|| "Invalid commitment bytes".to_string())?;
-
-        // Create witness before appending
-        let witness = IncrementalWitness::from_tree(self.tree.clone())
-            .ok_or_else(|| "Failed to create witness from tree".to_string())?;
-
-        // Append to tree and get position
-        self.tree.append(cmx_hash)
-            .map_err(|e| format!("Failed to add to tree: {:?}", e))?;
+        self.append_commitment(cmx)?;
 
-        let position = Position::from(self.tree.size() as u64 - 1);
+        let position = self.tree.mark()
+            .ok_or_else(|| "Failed to mark position for note".to_string())?;
 
         // Store the spendable note
         let spendable = SpendableNote {
             note,
             position,
-            witness,
             cmx,
             nullifier,
+            asset_id,
             amount,
             ledger_seq,
             tx_hash,
+            output_index,
         };
 
+        self.nullifier_index.insert(nullifier, cmx);
         self.notes.insert(cmx, spendable);
         Ok(())
     }
 
     /// Mark a note as spent by its nullifier
     pub fn mark_spent(&mut self, nullifier: &[u8; 32]) {
-        // Find note with this nullifier and mark as spent
-        if let Some((cmx, _)) = self.notes.iter()
-            .find(|(_, note)| &note.nullifier == nullifier)
-        {
+        if let Some(cmx) = self.nullifier_index.get(nullifier) {
             let cmx = *cmx;
             self.spent_notes.insert(cmx);
             self.notes.remove(&cmx);
         }
     }
 
+    /// Look up a note by its [`NoteId`] (transaction hash + output index)
+    pub fn get_note_by_id(&self, id: &NoteId) -> Option<&SpendableNote> {
+        self.notes.values().find(|n| n.tx_hash == id.tx_hash && n.output_index == id.output_index)
+    }
+
+    /// Look up a note by its nullifier
+    pub fn get_note_by_nullifier(&self, nullifier: &[u8; 32]) -> Option<&SpendableNote> {
+        self.nullifier_index.get(nullifier).and_then(|cmx| self.notes.get(cmx))
+    }
+
+    /// All unspent notes received in a given transaction
+    pub fn notes_in_tx(&self, tx_hash: &[u8; 32]) -> Vec<&SpendableNote> {
+        self.notes.values().filter(|n| &n.tx_hash == tx_hash).collect()
+    }
+
     /// Get the current anchor (Merkle tree root)
     pub fn get_anchor(&self) -> Result<Anchor, String> {
-        let root = self.tree.root();
-        let anchor_bytes = root.to_bytes();
+        let root = self.tree.root(0)
+            .ok_or_else(|| "Tree is empty, no anchor available".to_string())?;
 
-        Anchor::from_bytes(anchor_bytes)
+        Anchor::from_bytes(root.to_bytes())
             .into_option()
             .ok_or_else(|| "Failed to create anchor from tree root".to_string())
     }
 
     /// Generate a witness path for a specific note
+    ///
+    /// Derived on demand from the note's `Position` against the current root,
+    /// so it's always consistent with the latest anchor even after other
+    /// notes have been appended since this one was received.
     pub fn get_witness_path(&self, cmx: &[u8; 32]) -> Result<MerklePath, String> {
         let note = self.notes.get(cmx)
             .ok_or_else(|| "Note not found".to_string())?;
 
-        // Generate merkle path from stored witness
-        // The witness.path() returns the incrementalmerkletree MerklePath
-        let inc_merkle_path = note.witness.path()
-            .ok_or_else(|| "Failed to generate authentication path".to_string())?;
+        let auth_path_vec = self.tree.witness(note.position, 0)
+            .map_err(|e| format!("Failed to generate authentication path: {:?}", e))?;
 
         // Convert position to u32 for Orchard's MerklePath
         let position_u32: u32 = u64::from(note.position).try_into()
             .map_err(|_| "Position too large for u32".to_string())?;
 
-        // Extract auth path as array from incrementalmerkletree::MerklePath
-        let auth_path_vec: Vec<_> = inc_merkle_path.path_elems().iter().copied().collect();
         let mut auth_path = [MerkleHashOrchard::empty_leaf(); 32];
         for (i, elem) in auth_path_vec.iter().enumerate().take(32) {
             auth_path[i] = *elem;
@@ -152,33 +348,78 @@ impl NoteManager {
         Ok(MerklePath::from_parts(position_u32, auth_path))
     }
 
-    /// Select notes to spend for a given amount
+    /// Select notes to spend for a given amount of a specific asset
     ///
-    /// Uses a simple greedy algorithm: pick smallest notes that sum to amount
-    pub fn select_notes(&self, amount_needed: u64) -> Result<Vec<[u8; 32]>, String> {
+    /// Thin wrapper around [`NoteManager::select_notes_for_asset_with_fee`]
+    /// with no fee and no preference for avoiding a change output.
+    pub fn select_notes(&self, amount_needed: u64, asset_id: AssetId) -> Result<Vec<[u8; 32]>, String> {
+        self.select_notes_for_asset_with_fee(amount_needed, 0, 0, asset_id)
+    }
+
+    /// Select notes to spend `amount_needed` plus `fee` of a specific asset,
+    /// minimizing change and action count
+    ///
+    /// Orchard bundles pad to a minimum of two actions regardless of how many
+    /// notes are spent, so fewer, better-fitting notes reduce proving cost
+    /// without a downside. Runs a branch-and-bound search over the
+    /// sorted-descending candidates (as in Bitcoin Core's coin selection):
+    /// each candidate is either included or excluded, pruning any branch that
+    /// can no longer reach `target` or that already overshoots
+    /// `target + cost_of_change`, and keeping the closest-to-exact subset
+    /// found within a bounded number of tries. If no such subset exists,
+    /// falls back to single random draw: shuffle the candidates and
+    /// accumulate until `target` is met.
+    pub fn select_notes_with_fee(
+        &self,
+        amount_needed: u64,
+        fee: u64,
+        cost_of_change: u64,
+    ) -> Result<Vec<[u8; 32]>, String> {
+        self.select_notes_for_asset_with_fee(amount_needed, fee, cost_of_change, NATIVE_ASSET)
+    }
+
+    /// Same as [`NoteManager::select_notes_with_fee`], but for an arbitrary asset
+    pub fn select_notes_for_asset_with_fee(
+        &self,
+        amount_needed: u64,
+        fee: u64,
+        cost_of_change: u64,
+        asset_id: AssetId,
+    ) -> Result<Vec<[u8; 32]>, String> {
+        let target = amount_needed.checked_add(fee)
+            .ok_or_else(|| "Amount overflow".to_string())?;
+
         let mut available: Vec<_> = self.notes.values()
-            .filter(|n| !self.spent_notes.contains(&n.cmx))
+            .filter(|n| !self.spent_notes.contains(&n.cmx) && n.asset_id == asset_id)
             .collect();
 
-        // Sort by amount (smallest first for privacy)
-        available.sort_by_key(|n| n.amount);
+        // Branch-and-bound explores largest notes first so it converges on a
+        // small subset quickly.
+        available.sort_by_key(|n| std::cmp::Reverse(n.amount));
+
+        if let Some(selected) = branch_and_bound(&available, target, cost_of_change) {
+            return Ok(selected.into_iter().map(|n| n.cmx).collect());
+        }
+
+        // No near-exact match: fall back to single random draw.
+        let mut shuffled = available.clone();
+        shuffled.shuffle(&mut rand::rngs::OsRng);
 
         let mut selected = Vec::new();
         let mut total = 0u64;
-
-        for note in available {
+        for note in shuffled {
             selected.push(note.cmx);
             total = total.checked_add(note.amount)
                 .ok_or_else(|| "Amount overflow".to_string())?;
 
-            if total >= amount_needed {
+            if total >= target {
                 return Ok(selected);
             }
         }
 
         Err(format!(
             "Insufficient balance: have {}, need {}",
-            total, amount_needed
+            total, target
         ))
     }
 
@@ -187,17 +428,247 @@ impl NoteManager {
         self.notes.get(cmx)
     }
 
-    /// Get total balance (unspent notes only)
-    pub fn get_balance(&self) -> u64 {
+    /// Get total balance for a single asset (unspent notes only)
+    pub fn get_balance(&self, asset_id: AssetId) -> u64 {
         self.notes.values()
-            .filter(|n| !self.spent_notes.contains(&n.cmx))
+            .filter(|n| !self.spent_notes.contains(&n.cmx) && n.asset_id == asset_id)
             .map(|n| n.amount)
             .sum()
     }
 
-    /// Get number of unspent notes
-    pub fn note_count(&self) -> usize {
-        self.notes.len() - self.spent_notes.len()
+    /// Get unspent balances for every asset currently held
+    pub fn get_balances(&self) -> HashMap<AssetId, u64> {
+        let mut balances = HashMap::new();
+        for note in self.notes.values().filter(|n| !self.spent_notes.contains(&n.cmx)) {
+            *balances.entry(note.asset_id).or_insert(0) += note.amount;
+        }
+        balances
+    }
+
+    /// Get number of unspent notes, optionally filtered to a single asset
+    pub fn note_count(&self, asset_id: Option<AssetId>) -> usize {
+        self.notes.values()
+            .filter(|n| !self.spent_notes.contains(&n.cmx))
+            .filter(|n| asset_id.map_or(true, |id| n.asset_id == id))
+            .count()
+    }
+
+    /// Serialize the complete manager state (tree, notes, spent set)
+    ///
+    /// Uses a small versioned binary format, writing each component with an
+    /// explicit length prefix (mirroring the ZIP-225 component encoding used
+    /// for Orchard bundles) so a wallet can persist its shielded state across
+    /// restarts instead of rescanning from genesis. The `BridgeTree` carries
+    /// its own checkpoint and mark bookkeeping, so it's written whole (as
+    /// JSON, since Orchard's tree hash type isn't `bincode`-friendly) rather
+    /// than decomposed field by field; witnesses are never stored directly,
+    /// they're derived on demand from each note's `position` after loading.
+    pub fn write<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(&[SERIALIZATION_VERSION])?;
+
+        let tree_json = serde_json::to_vec(&self.tree)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Failed to serialize tree: {}", e)))?;
+        w.write_all(&(tree_json.len() as u64).to_le_bytes())?;
+        w.write_all(&tree_json)?;
+
+        w.write_all(&(self.notes.len() as u32).to_le_bytes())?;
+        for note in self.notes.values() {
+            w.write_all(&note.cmx)?;
+            w.write_all(&note.nullifier)?;
+            w.write_all(&note.asset_id)?;
+            w.write_all(&note.amount.to_le_bytes())?;
+            w.write_all(&note.ledger_seq.to_le_bytes())?;
+            w.write_all(&note.tx_hash)?;
+            w.write_all(&note.output_index.to_le_bytes())?;
+            w.write_all(&u64::from(note.position).to_le_bytes())?;
+
+            w.write_all(&note.note.recipient().to_raw_address_bytes())?;
+            w.write_all(&note.note.rho().to_bytes())?;
+            w.write_all(note.note.rseed().as_bytes())?;
+        }
+
+        w.write_all(&(self.spent_notes.len() as u32).to_le_bytes())?;
+        for cmx in &self.spent_notes {
+            w.write_all(cmx)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deserialize a manager state previously produced by [`NoteManager::write`]
+    pub fn read<R: Read>(mut r: R) -> io::Result<Self> {
+        let mut version = [0u8; 1];
+        r.read_exact(&mut version)?;
+        if version[0] != SERIALIZATION_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported NoteManager serialization version {}", version[0]),
+            ));
+        }
+
+        let tree_len = read_u64(&mut r)? as usize;
+        let mut tree_json = vec![0u8; tree_len];
+        r.read_exact(&mut tree_json)?;
+        let tree = serde_json::from_slice(&tree_json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Failed to deserialize tree: {}", e)))?;
+
+        let mut notes = HashMap::new();
+        let note_count = read_u32(&mut r)?;
+        for _ in 0..note_count {
+            let cmx = read_array::<_, 32>(&mut r)?;
+            let nullifier = read_array::<_, 32>(&mut r)?;
+            let asset_id = read_array::<_, 32>(&mut r)?;
+            let amount = read_u64(&mut r)?;
+            let ledger_seq = read_u32(&mut r)?;
+            let tx_hash = read_array::<_, 32>(&mut r)?;
+            let output_index = read_u32(&mut r)?;
+            let position = Position::from(read_u64(&mut r)?);
+
+            let recipient_bytes = read_array::<_, 43>(&mut r)?;
+            let recipient = Address::from_raw_address_bytes(&recipient_bytes)
+                .into_option()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid note recipient address"))?;
+            let rho_bytes = read_array::<_, 32>(&mut r)?;
+            let rho = Rho::from_bytes(&rho_bytes)
+                .into_option()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid note rho"))?;
+            let rseed_bytes = read_array::<_, 32>(&mut r)?;
+            let rseed = RandomSeed::from_bytes(rseed_bytes, &rho)
+                .into_option()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid note random seed"))?;
+            let note = Note::from_parts(recipient, NoteValue::from_raw(amount), rho, rseed)
+                .into_option()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid note parts"))?;
+
+            notes.insert(cmx, SpendableNote {
+                note,
+                position,
+                cmx,
+                nullifier,
+                asset_id,
+                amount,
+                ledger_seq,
+                tx_hash,
+                output_index,
+            });
+        }
+
+        let mut spent_notes = std::collections::HashSet::new();
+        let spent_count = read_u32(&mut r)?;
+        for _ in 0..spent_count {
+            spent_notes.insert(read_array::<_, 32>(&mut r)?);
+        }
+
+        let nullifier_index = notes.values().map(|n| (n.nullifier, n.cmx)).collect();
+
+        Ok(Self {
+            notes,
+            tree,
+            spent_notes,
+            nullifier_index,
+            checkpoints: Vec::new(),
+        })
+    }
+}
+
+/// Depth-first include/exclude search for the subset of `candidates` (sorted
+/// largest-first) whose sum lands closest to `target` within
+/// `[target, target + cost_of_change]`, pruning branches that can't reach
+/// `target` or that already overshoot the upper bound. Returns `None` if no
+/// such subset is found within [`BNB_MAX_TRIES`] branches.
+fn branch_and_bound<'a>(
+    candidates: &[&'a SpendableNote],
+    target: u64,
+    cost_of_change: u64,
+) -> Option<Vec<&'a SpendableNote>> {
+    let upper_bound = target.saturating_add(cost_of_change);
+
+    // Suffix sums so a branch can be pruned once the remaining candidates
+    // can't possibly bridge the gap to `target`.
+    let mut remaining_sum = vec![0u64; candidates.len() + 1];
+    for i in (0..candidates.len()).rev() {
+        remaining_sum[i] = remaining_sum[i + 1].saturating_add(candidates[i].amount);
+    }
+
+    let mut best: Option<(u64, Vec<&'a SpendableNote>)> = None;
+    let mut current = Vec::new();
+    let mut tries = 0usize;
+
+    fn visit<'a>(
+        candidates: &[&'a SpendableNote],
+        remaining_sum: &[u64],
+        index: usize,
+        sum: u64,
+        target: u64,
+        upper_bound: u64,
+        current: &mut Vec<&'a SpendableNote>,
+        best: &mut Option<(u64, Vec<&'a SpendableNote>)>,
+        tries: &mut usize,
+    ) {
+        *tries += 1;
+        if *tries > BNB_MAX_TRIES {
+            return;
+        }
+
+        if sum >= target {
+            if sum <= upper_bound && best.as_ref().map_or(true, |(best_sum, _)| sum < *best_sum) {
+                *best = Some((sum, current.clone()));
+            }
+            return;
+        }
+
+        if index == candidates.len() || sum + remaining_sum[index] < target {
+            return;
+        }
+
+        // Include candidates[index]
+        current.push(candidates[index]);
+        visit(candidates, remaining_sum, index + 1, sum + candidates[index].amount, target, upper_bound, current, best, tries);
+        current.pop();
+
+        // Exclude candidates[index]
+        visit(candidates, remaining_sum, index + 1, sum, target, upper_bound, current, best, tries);
+    }
+
+    visit(candidates, &remaining_sum, 0, 0, target, upper_bound, &mut current, &mut best, &mut tries);
+
+    best.map(|(_, selected)| selected)
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_array<R: Read, const N: usize>(r: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+// Orchard's cryptographic types don't implement `serde` themselves, so these
+// impls just wrap the binary format from `NoteManager::write`/`read`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for NoteManager {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut buf = Vec::new();
+        self.write(&mut buf).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(&buf)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NoteManager {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes: Vec<u8> = serde::Deserialize::deserialize(deserializer)?;
+        Self::read(&bytes[..]).map_err(serde::de::Error::custom)
     }
 }
 
@@ -234,16 +705,124 @@ mod tests {
         let nullifier = [4u8; 32];
 
         // Add note
-        manager.add_note(note, cmx, nullifier, 100, [5u8; 32]).unwrap();
+        manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 100, [5u8; 32], 0).unwrap();
 
         // Check balance
-        assert_eq!(manager.get_balance(), 1000);
-        assert_eq!(manager.note_count(), 1);
+        assert_eq!(manager.get_balance(NATIVE_ASSET), 1000);
+        assert_eq!(manager.note_count(None), 1);
 
         // Mark as spent
         manager.mark_spent(&nullifier);
-        assert_eq!(manager.get_balance(), 0);
-        assert_eq!(manager.note_count(), 0);
+        assert_eq!(manager.get_balance(NATIVE_ASSET), 0);
+        assert_eq!(manager.note_count(None), 0);
+    }
+
+    #[test]
+    fn test_serialize_deserialize_note_round_trip() {
+        let sk = SpendingKey::from_bytes([7u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        let rho = Rho::from_bytes(&[8u8; 32]).unwrap();
+        let rseed = RandomSeed::from_bytes([9u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(4200), rho, rseed).unwrap();
+        let cmx = orchard::note::ExtractedNoteCommitment::from(note.commitment()).to_bytes();
+
+        let bytes = serialize_note(&note);
+        assert_eq!(bytes.len(), SERIALIZED_NOTE_LEN);
+
+        let restored = deserialize_note(&bytes, &cmx).unwrap();
+        assert_eq!(restored.value().inner(), 4200);
+        assert_eq!(restored.recipient(), note.recipient());
+    }
+
+    #[test]
+    fn test_deserialize_note_rejects_wrong_cmx() {
+        let sk = SpendingKey::from_bytes([7u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        let rho = Rho::from_bytes(&[8u8; 32]).unwrap();
+        let rseed = RandomSeed::from_bytes([9u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(4200), rho, rseed).unwrap();
+
+        let bytes = serialize_note(&note);
+        assert!(deserialize_note(&bytes, &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_asset_balances_kept_separate() {
+        let mut manager = NoteManager::new();
+
+        let sk_bytes = [1u8; 32];
+        let sk = SpendingKey::from_bytes(sk_bytes).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        let zsa_asset: AssetId = [9u8; 32];
+
+        for (i, asset) in [NATIVE_ASSET, zsa_asset].into_iter().enumerate() {
+            let i = i as u8;
+            let rho = Rho::from_bytes(&[i; 32]).unwrap();
+            let rseed = RandomSeed::from_bytes([i + 20; 32], &rho).unwrap();
+            let note = Note::from_parts(addr, NoteValue::from_raw(500), rho, rseed).unwrap();
+
+            let mut cmx = [0u8; 32];
+            cmx[0] = i + 50;
+            let mut nullifier = [0u8; 32];
+            nullifier[0] = i + 150;
+
+            manager.add_note(note, cmx, nullifier, asset, 100, [i; 32], 0).unwrap();
+        }
+
+        assert_eq!(manager.get_balance(NATIVE_ASSET), 500);
+        assert_eq!(manager.get_balance(zsa_asset), 500);
+        assert_eq!(manager.get_balances().len(), 2);
+        assert_eq!(manager.note_count(Some(zsa_asset)), 1);
+
+        // Selecting native value must not pick up the ZSA note
+        assert!(manager.select_notes(1000, NATIVE_ASSET).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_and_rewind() {
+        let mut manager = NoteManager::new();
+
+        let sk_bytes = [1u8; 32];
+        let sk = SpendingKey::from_bytes(sk_bytes).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        // Ledger 100: receive one note, then checkpoint
+        let rho = Rho::from_bytes(&[1u8; 32]).unwrap();
+        let rseed = RandomSeed::from_bytes([11u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(1000), rho, rseed).unwrap();
+        let cmx = [1u8; 32];
+        let nullifier = [101u8; 32];
+        manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 100, [1u8; 32], 0).unwrap();
+        manager.checkpoint(100);
+
+        // Ledger 101: receive a second note and spend the first, then checkpoint
+        let rho2 = Rho::from_bytes(&[2u8; 32]).unwrap();
+        let rseed2 = RandomSeed::from_bytes([12u8; 32], &rho2).unwrap();
+        let note2 = Note::from_parts(addr, NoteValue::from_raw(2000), rho2, rseed2).unwrap();
+        let cmx2 = [2u8; 32];
+        let nullifier2 = [102u8; 32];
+        manager.add_note(note2, cmx2, nullifier2, NATIVE_ASSET, 101, [2u8; 32], 0).unwrap();
+        manager.mark_spent(&nullifier);
+        manager.checkpoint(101);
+
+        assert_eq!(manager.get_balance(NATIVE_ASSET), 2000);
+
+        // Reorg: rewind to ledger 100 should undo both the spend and the second note
+        manager.rewind_to(100).unwrap();
+        assert_eq!(manager.get_balance(NATIVE_ASSET), 1000);
+        assert_eq!(manager.note_count(None), 1);
+        assert!(manager.get_note(&cmx).is_some());
+        assert!(manager.get_note(&cmx2).is_none());
+
+        // Rewinding to a ledger we never checkpointed is an error
+        assert!(manager.rewind_to(999).is_err());
     }
 
     #[test]
@@ -271,17 +850,163 @@ mod tests {
             let mut nullifier = [0u8; 32];
             nullifier[0] = i + 100;
 
-            manager.add_note(note, cmx, nullifier, 100, [i; 32]).unwrap();
+            manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 100, [i; 32], 0).unwrap();
         }
 
-        // Select notes for 2500 (should pick 1000 + 2000)
-        let selected = manager.select_notes(2500).unwrap();
-        assert_eq!(selected.len(), 2);
-
-        // Check total
+        // Select notes for 2500: no subset sums exactly to 2500, so this
+        // falls back to single random draw, but must still cover the amount.
+        let selected = manager.select_notes(2500, NATIVE_ASSET).unwrap();
         let total: u64 = selected.iter()
             .map(|cmx| manager.get_note(cmx).unwrap().amount)
             .sum();
         assert!(total >= 2500);
     }
+
+    #[test]
+    fn test_branch_and_bound_prefers_exact_fit() {
+        let mut manager = NoteManager::new();
+
+        let sk_bytes = [1u8; 32];
+        let sk = SpendingKey::from_bytes(sk_bytes).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        // Notes of 1000, 2000, 4000, 5000: the only exact-sum match for a
+        // target of 3000 is {1000, 2000}, even though 4000 alone would cover
+        // it with a single note.
+        for (i, amount) in [1000u64, 2000, 4000, 5000].into_iter().enumerate() {
+            let i = i as u8;
+            let rho = Rho::from_bytes(&[i; 32]).unwrap();
+            let rseed = RandomSeed::from_bytes([i + 10; 32], &rho).unwrap();
+            let note = Note::from_parts(addr, NoteValue::from_raw(amount), rho, rseed).unwrap();
+
+            let mut cmx = [0u8; 32];
+            cmx[0] = i;
+            let mut nullifier = [0u8; 32];
+            nullifier[0] = i + 100;
+
+            manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 100, [i; 32], 0).unwrap();
+        }
+
+        let selected = manager.select_notes_with_fee(3000, 0, 0).unwrap();
+        let total: u64 = selected.iter()
+            .map(|cmx| manager.get_note(cmx).unwrap().amount)
+            .sum();
+        assert_eq!(total, 3000);
+        assert_eq!(selected.len(), 2);
+    }
+
+    #[test]
+    fn test_witness_stays_valid_after_later_notes() {
+        let mut manager = NoteManager::new();
+
+        let sk_bytes = [1u8; 32];
+        let sk = SpendingKey::from_bytes(sk_bytes).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        let mut first_cmx = [0u8; 32];
+
+        // Add three notes to the same manager
+        for i in 0..3u8 {
+            let rho = Rho::from_bytes(&[i; 32]).unwrap();
+            let rseed = RandomSeed::from_bytes([i + 30; 32], &rho).unwrap();
+            let note = Note::from_parts(addr, NoteValue::from_raw(1000), rho, rseed).unwrap();
+
+            let mut cmx = [0u8; 32];
+            cmx[0] = i + 1;
+            let mut nullifier = [0u8; 32];
+            nullifier[0] = i + 200;
+
+            manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 100, [i; 32], 0).unwrap();
+
+            if i == 0 {
+                first_cmx = cmx;
+            }
+        }
+
+        // The first note's witness path must still validate against the
+        // final root, after the second and third notes were appended.
+        let path = manager.get_witness_path(&first_cmx).unwrap();
+        let first_note = manager.get_note(&first_cmx).unwrap();
+        let extracted_cmx = orchard::note::ExtractedNoteCommitment::from(first_note.note.commitment());
+        let computed_anchor = path.root(extracted_cmx);
+        assert_eq!(computed_anchor, manager.get_anchor().unwrap());
+    }
+
+    #[test]
+    fn test_write_read_round_trip() {
+        let mut manager = NoteManager::new();
+
+        let sk_bytes = [1u8; 32];
+        let sk = SpendingKey::from_bytes(sk_bytes).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        let rho = Rho::from_bytes(&[7u8; 32]).unwrap();
+        let rseed = RandomSeed::from_bytes([8u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(4200), rho, rseed).unwrap();
+
+        let cmx = [9u8; 32];
+        let nullifier = [10u8; 32];
+        manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 50, [11u8; 32], 0).unwrap();
+
+        let mut bytes = Vec::new();
+        manager.write(&mut bytes).unwrap();
+
+        let restored = NoteManager::read(&bytes[..]).unwrap();
+        assert_eq!(restored.get_balance(NATIVE_ASSET), 4200);
+        assert_eq!(restored.note_count(None), 1);
+        assert_eq!(restored.get_anchor().unwrap(), manager.get_anchor().unwrap());
+
+        // The restored manager must still be able to produce a witness path for the note
+        assert!(restored.get_witness_path(&cmx).is_ok());
+    }
+
+    #[test]
+    fn test_lookup_by_note_id_and_nullifier() {
+        let mut manager = NoteManager::new();
+
+        let sk_bytes = [1u8; 32];
+        let sk = SpendingKey::from_bytes(sk_bytes).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+
+        let tx_hash = [42u8; 32];
+
+        // Two outputs of the same transaction
+        let mut cmxs = Vec::new();
+        let mut nullifiers = Vec::new();
+        for i in 0..2u8 {
+            let rho = Rho::from_bytes(&[i; 32]).unwrap();
+            let rseed = RandomSeed::from_bytes([i + 60; 32], &rho).unwrap();
+            let note = Note::from_parts(addr, NoteValue::from_raw(1000), rho, rseed).unwrap();
+
+            let mut cmx = [0u8; 32];
+            cmx[0] = i + 1;
+            let mut nullifier = [0u8; 32];
+            nullifier[0] = i + 210;
+
+            manager.add_note(note, cmx, nullifier, NATIVE_ASSET, 100, tx_hash, i as u32).unwrap();
+            cmxs.push(cmx);
+            nullifiers.push(nullifier);
+        }
+
+        // Both outputs are reachable by (tx_hash, output_index)
+        let found = manager.get_note_by_id(&NoteId { tx_hash, output_index: 0 }).unwrap();
+        assert_eq!(found.cmx, cmxs[0]);
+        assert_eq!(found.note_id(), NoteId { tx_hash, output_index: 0 });
+
+        // ...and by nullifier
+        let found = manager.get_note_by_nullifier(&nullifiers[1]).unwrap();
+        assert_eq!(found.cmx, cmxs[1]);
+
+        // notes_in_tx returns both
+        assert_eq!(manager.notes_in_tx(&tx_hash).len(), 2);
+
+        // Spending one drops it from lookups but keeps the other
+        manager.mark_spent(&nullifiers[0]);
+        assert!(manager.get_note_by_nullifier(&nullifiers[0]).is_none());
+        assert_eq!(manager.notes_in_tx(&tx_hash).len(), 1);
+    }
 }
@@ -0,0 +1,500 @@
+//! Sharded, pruning-capable commitment tree storage
+//!
+//! Keeping the entire depth-32 commitment tree resident in memory (as a
+//! single `BridgeTree`) does not scale for a server tracking a full ledger
+//! history: memory grows with every commitment ever seen, not just the ones
+//! we actually need to witness.
+//!
+//! This module splits the tree into fixed-depth subtrees ("shards"), each
+//! covering [`SHARD_HEIGHT`] levels (65,536 leaves), with a small "cap" tree
+//! of the remaining [`CAP_HEIGHT`] levels sitting above them - one cap leaf
+//! per completed shard. A shard that completes without holding any of our
+//! marked positions is pruned down to just its root hash, since we'll never
+//! need to produce a witness through it; a shard holding one of our notes is
+//! kept in full so a witness can still be derived on demand. This mirrors
+//! the shardtree-based note-commitment design used in zcash_client_backend
+//! block scanning, scoped down to what this crate actually needs.
+//!
+//! Storage is abstracted behind the [`ShardStore`] trait so an in-memory
+//! implementation ([`MemoryShardStore`]) can later be swapped for a
+//! disk-backed one without changing [`ShardedCommitmentTree`] itself.
+
+use bridgetree::BridgeTree;
+use incrementalmerkletree::Position;
+use orchard::tree::MerkleHashOrchard;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of levels covered by a single shard (2^16 = 65,536 leaves).
+pub const SHARD_HEIGHT: usize = 16;
+/// Number of levels covered by the cap tree above the shards.
+///
+/// Orchard's commitment tree is 32 levels deep, split evenly between shards
+/// and the cap that combines their roots.
+pub const CAP_HEIGHT: usize = 32 - SHARD_HEIGHT;
+const SHARD_SIZE: u64 = 1 << SHARD_HEIGHT as u32;
+const MAX_CHECKPOINTS: usize = 100;
+
+type ShardBridgeTree = BridgeTree<MerkleHashOrchard, u32, SHARD_HEIGHT>;
+type CapBridgeTree = BridgeTree<MerkleHashOrchard, u32, CAP_HEIGHT>;
+
+/// A snapshot of which shard was being filled, and how much of it, at the
+/// moment a checkpoint was taken - enough to restore
+/// [`ShardedCommitmentTree`]'s own bookkeeping on rewind, mirroring the
+/// snapshot-based checkpoint/rewind approach used elsewhere in this crate.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ShardCheckpoint {
+    pub ledger_seq: u32,
+    pub active_shard_index: u64,
+    pub active_shard_size: u64,
+    pub active_shard_has_marks: bool,
+}
+
+/// A single fixed-depth subtree of the overall commitment tree.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Shard {
+    /// Completed, with none of our marked positions inside: only the root
+    /// hash is retained. A pruned shard can never be witnessed through.
+    Pruned(MerkleHashOrchard),
+    /// Still filling, or completed but holding one or more of our marked
+    /// positions: kept in full (serialized) so it can still be appended to
+    /// or witnessed through.
+    Full(Vec<u8>),
+}
+
+/// Storage backing for a [`ShardedCommitmentTree`].
+///
+/// Implementations decide how shards, the cap, and checkpoint bookkeeping
+/// are actually persisted. [`MemoryShardStore`] keeps everything resident;
+/// a disk-backed implementation can later evict pruned or cold shards to
+/// real storage while honoring the same trait.
+pub trait ShardStore {
+    type Error: std::fmt::Debug;
+
+    /// Fetch a shard by index, if it has ever been written.
+    fn get_shard(&self, shard_index: u64) -> Result<Option<Shard>, Self::Error>;
+    /// Persist (or replace) a shard by index.
+    fn put_shard(&mut self, shard_index: u64, shard: Shard) -> Result<(), Self::Error>;
+    /// Fetch the serialized cap tree, or an empty vec if none has been written yet.
+    fn get_cap(&self) -> Result<Vec<u8>, Self::Error>;
+    /// Persist the serialized cap tree.
+    fn put_cap(&mut self, cap: Vec<u8>) -> Result<(), Self::Error>;
+    /// Record a checkpoint. Oldest checkpoints beyond the retention limit
+    /// may be dropped.
+    fn add_checkpoint(&mut self, checkpoint: ShardCheckpoint) -> Result<(), Self::Error>;
+    /// Drop all checkpoints beyond the most recent `keep`.
+    fn truncate(&mut self, keep: usize) -> Result<(), Self::Error>;
+    /// List recorded checkpoints, oldest first.
+    fn checkpoints(&self) -> Result<Vec<ShardCheckpoint>, Self::Error>;
+}
+
+/// In-memory [`ShardStore`] implementation.
+///
+/// Nothing here survives a restart on its own - [`ShardedCommitmentTree`] is
+/// persisted as a whole alongside the rest of wallet state. A disk-backed
+/// store can be added later by implementing the same trait against real
+/// storage and loading/evicting shards lazily instead of keeping them all
+/// resident.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MemoryShardStore {
+    shards: BTreeMap<u64, Shard>,
+    cap: Vec<u8>,
+    checkpoints: Vec<ShardCheckpoint>,
+}
+
+impl MemoryShardStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ShardStore for MemoryShardStore {
+    type Error = std::convert::Infallible;
+
+    fn get_shard(&self, shard_index: u64) -> Result<Option<Shard>, Self::Error> {
+        Ok(self.shards.get(&shard_index).cloned())
+    }
+
+    fn put_shard(&mut self, shard_index: u64, shard: Shard) -> Result<(), Self::Error> {
+        self.shards.insert(shard_index, shard);
+        Ok(())
+    }
+
+    fn get_cap(&self) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.cap.clone())
+    }
+
+    fn put_cap(&mut self, cap: Vec<u8>) -> Result<(), Self::Error> {
+        self.cap = cap;
+        Ok(())
+    }
+
+    fn add_checkpoint(&mut self, checkpoint: ShardCheckpoint) -> Result<(), Self::Error> {
+        self.checkpoints.push(checkpoint);
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+        Ok(())
+    }
+
+    fn truncate(&mut self, keep: usize) -> Result<(), Self::Error> {
+        let len = self.checkpoints.len();
+        if len > keep {
+            self.checkpoints.drain(0..len - keep);
+        }
+        Ok(())
+    }
+
+    fn checkpoints(&self) -> Result<Vec<ShardCheckpoint>, Self::Error> {
+        Ok(self.checkpoints.clone())
+    }
+}
+
+/// A depth-32 commitment tree split into shards of [`SHARD_HEIGHT`] levels
+/// each, with a [`CAP_HEIGHT`]-level cap tree combining their roots.
+///
+/// Exposes the same operations `NoteManager`'s `BridgeTree` did
+/// (`append`/`mark`/`witness`/`root`/`checkpoint`/`rewind`), so it can be
+/// dropped into `OrchardWalletState` as a direct replacement while bounding
+/// memory to the shards that actually hold one of our notes.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ShardedCommitmentTree<S: ShardStore> {
+    store: S,
+    active_shard_index: u64,
+    active_shard_size: u64,
+    active_shard_has_marks: bool,
+}
+
+impl<S: ShardStore> ShardedCommitmentTree<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            active_shard_index: 0,
+            active_shard_size: 0,
+            active_shard_has_marks: false,
+        }
+    }
+
+    fn load_active_shard(&self) -> Result<ShardBridgeTree, String> {
+        match self.store.get_shard(self.active_shard_index).map_err(|e| format!("{:?}", e))? {
+            Some(Shard::Full(bytes)) => serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Failed to deserialize active shard: {}", e)),
+            Some(Shard::Pruned(_)) => Err("Active shard is unexpectedly pruned".to_string()),
+            None => Ok(ShardBridgeTree::new(MAX_CHECKPOINTS)),
+        }
+    }
+
+    fn save_active_shard(&mut self, tree: &ShardBridgeTree) -> Result<(), String> {
+        let bytes = serde_json::to_vec(tree)
+            .map_err(|e| format!("Failed to serialize active shard: {}", e))?;
+        self.store
+            .put_shard(self.active_shard_index, Shard::Full(bytes))
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    fn load_cap(&self) -> Result<CapBridgeTree, String> {
+        let bytes = self.store.get_cap().map_err(|e| format!("{:?}", e))?;
+        if bytes.is_empty() {
+            Ok(CapBridgeTree::new(MAX_CHECKPOINTS))
+        } else {
+            serde_json::from_slice(&bytes).map_err(|e| format!("Failed to deserialize cap: {}", e))
+        }
+    }
+
+    fn save_cap(&mut self, tree: &CapBridgeTree) -> Result<(), String> {
+        let bytes = serde_json::to_vec(tree).map_err(|e| format!("Failed to serialize cap: {}", e))?;
+        self.store.put_cap(bytes).map_err(|e| format!("{:?}", e))
+    }
+
+    /// If the active shard filled up on a prior append, finalize it: roll
+    /// its root into the cap (marking the cap leaf too if the shard held any
+    /// of our notes), persist it pruned or full accordingly, and move on to
+    /// a fresh shard. This is deferred to the start of the *next* append
+    /// (rather than done eagerly once a shard reaches capacity) so that a
+    /// `mark()` immediately following the append that completed a shard
+    /// still marks a position within that same, now-completed shard.
+    fn finalize_active_shard_if_full(&mut self) -> Result<(), String> {
+        if self.active_shard_size < SHARD_SIZE {
+            return Ok(());
+        }
+
+        let shard = self.load_active_shard()?;
+        let shard_root = shard
+            .root(0)
+            .ok_or_else(|| "Completed shard unexpectedly has no root".to_string())?;
+
+        let mut cap = self.load_cap()?;
+        cap.append(shard_root)
+            .then_some(())
+            .ok_or_else(|| "Failed to extend cap tree (cap full)".to_string())?;
+        if self.active_shard_has_marks {
+            cap.mark();
+        }
+        self.save_cap(&cap)?;
+
+        if self.active_shard_has_marks {
+            self.save_active_shard(&shard)?;
+        } else {
+            self.store
+                .put_shard(self.active_shard_index, Shard::Pruned(shard_root))
+                .map_err(|e| format!("{:?}", e))?;
+        }
+
+        self.active_shard_index += 1;
+        self.active_shard_size = 0;
+        self.active_shard_has_marks = false;
+        Ok(())
+    }
+
+    /// Append a commitment to the tree.
+    pub fn append(&mut self, cmx_hash: MerkleHashOrchard) -> bool {
+        self.try_append(cmx_hash).is_ok()
+    }
+
+    fn try_append(&mut self, cmx_hash: MerkleHashOrchard) -> Result<(), String> {
+        self.finalize_active_shard_if_full()?;
+
+        let mut shard = self.load_active_shard()?;
+        shard
+            .append(cmx_hash)
+            .then_some(())
+            .ok_or_else(|| "Failed to append to shard (shard full)".to_string())?;
+        self.active_shard_size += 1;
+        self.save_active_shard(&shard)
+    }
+
+    /// Mark the most recently appended commitment as ours, returning its
+    /// global position in the full depth-32 tree.
+    pub fn mark(&mut self) -> Option<Position> {
+        let mut shard = self.load_active_shard().ok()?;
+        let local_position = shard.mark()?;
+        self.active_shard_has_marks = true;
+        self.save_active_shard(&shard).ok()?;
+        let global_position = self.active_shard_index * SHARD_SIZE + u64::from(local_position);
+        Some(Position::from(global_position))
+    }
+
+    /// The current root of the full depth-32 tree, or `None` if nothing has
+    /// ever been appended.
+    pub fn root(&self, _checkpoint_depth: u8) -> Option<MerkleHashOrchard> {
+        let cap = self.load_cap().ok()?;
+        if self.active_shard_size == 0 {
+            return cap.root(0);
+        }
+        let active = self.load_active_shard().ok()?;
+        let active_root = active.root(0)?;
+        let mut tentative_cap = cap.clone();
+        tentative_cap.append(active_root).then_some(())?;
+        tentative_cap.root(0)
+    }
+
+    /// Generate an authentication path for `position`, spanning the full
+    /// depth-32 tree (shard-internal levels followed by cap levels).
+    ///
+    /// Only `checkpoint_depth == 0` (the current tree state) is supported;
+    /// the call sites in this crate never request anything deeper.
+    pub fn witness(&self, position: Position, checkpoint_depth: u8) -> Result<Vec<MerkleHashOrchard>, String> {
+        if checkpoint_depth != 0 {
+            return Err("ShardedCommitmentTree only supports checkpoint_depth 0".to_string());
+        }
+
+        let global_position = u64::from(position);
+        let shard_index = global_position / SHARD_SIZE;
+        let local_position = Position::from(global_position % SHARD_SIZE);
+
+        if shard_index == self.active_shard_index {
+            let active = self.load_active_shard()?;
+            let mut path = active
+                .witness(local_position, 0)
+                .map_err(|e| format!("Failed to witness active shard: {:?}", e))?;
+
+            let active_root = active
+                .root(0)
+                .ok_or_else(|| "Active shard unexpectedly empty".to_string())?;
+            let mut tentative_cap = self.load_cap()?;
+            tentative_cap
+                .append(active_root)
+                .then_some(())
+                .ok_or_else(|| "Failed to extend cap tree (cap full)".to_string())?;
+            let cap_position = tentative_cap
+                .mark()
+                .ok_or_else(|| "Failed to mark tentative cap leaf".to_string())?;
+            let cap_path = tentative_cap
+                .witness(cap_position, 0)
+                .map_err(|e| format!("Failed to witness cap tree: {:?}", e))?;
+
+            path.extend(cap_path);
+            Ok(path)
+        } else {
+            let shard = match self.store.get_shard(shard_index).map_err(|e| format!("{:?}", e))? {
+                Some(Shard::Full(bytes)) => serde_json::from_slice::<ShardBridgeTree>(&bytes)
+                    .map_err(|e| format!("Failed to deserialize shard {}: {}", shard_index, e))?,
+                Some(Shard::Pruned(_)) | None => {
+                    return Err(format!(
+                        "Cannot witness position in shard {}: shard is pruned or unknown (no note of ours there)",
+                        shard_index
+                    ))
+                }
+            };
+            let mut path = shard
+                .witness(local_position, 0)
+                .map_err(|e| format!("Failed to witness shard {}: {:?}", shard_index, e))?;
+
+            // root() also covers the partly filled active shard, so its root
+            // goes into the cap here too for the path to reach that root.
+            let mut cap = self.load_cap()?;
+            if self.active_shard_size > 0 {
+                let active_root = self
+                    .load_active_shard()?
+                    .root(0)
+                    .ok_or_else(|| "Active shard unexpectedly empty".to_string())?;
+                cap.append(active_root)
+                    .then_some(())
+                    .ok_or_else(|| "Failed to extend cap tree (cap full)".to_string())?;
+            }
+            let cap_path = cap
+                .witness(Position::from(shard_index), 0)
+                .map_err(|e| format!("Failed to witness cap tree: {:?}", e))?;
+            path.extend(cap_path);
+            Ok(path)
+        }
+    }
+
+    /// Checkpoint the tree at `ledger_seq`.
+    pub fn checkpoint(&mut self, ledger_seq: u32) -> bool {
+        let Ok(mut shard) = self.load_active_shard() else { return false };
+        let Ok(mut cap) = self.load_cap() else { return false };
+
+        let shard_ok = shard.checkpoint(ledger_seq);
+        let cap_ok = cap.checkpoint(ledger_seq);
+        if self.save_active_shard(&shard).is_err() || self.save_cap(&cap).is_err() {
+            return false;
+        }
+        let checkpoint = ShardCheckpoint {
+            ledger_seq,
+            active_shard_index: self.active_shard_index,
+            active_shard_size: self.active_shard_size,
+            active_shard_has_marks: self.active_shard_has_marks,
+        };
+        if self.store.add_checkpoint(checkpoint).is_err() {
+            return false;
+        }
+        shard_ok && cap_ok
+    }
+
+    /// Roll back to the checkpoint immediately before the current tip.
+    ///
+    /// Rewinding across a shard boundary (i.e. to a checkpoint taken before
+    /// the currently active shard was started) is not supported: shards
+    /// that have already been pruned cannot be reconstructed. This arises
+    /// whenever the checkpoint below the tip was taken before the active
+    /// shard was started, e.g. on the first rewind after the tree crosses
+    /// into a new shard; such a rewind returns an error and changes nothing.
+    pub fn rewind(&mut self) -> Result<bool, String> {
+        let checkpoints = self.store.checkpoints().map_err(|e| format!("{:?}", e))?;
+        if checkpoints.is_empty() {
+            return Err("No checkpoint to rewind to".to_string());
+        }
+        // The checkpoint we're landing on is the one below the tip being
+        // discarded; if the tip was the only checkpoint, we land on the
+        // empty, pre-checkpoint state.
+        let landing = checkpoints.len().checked_sub(2).map(|i| checkpoints[i]);
+        let landing_shard_index = landing.map_or(0, |c| c.active_shard_index);
+        if landing_shard_index != self.active_shard_index {
+            return Err(
+                "Cannot rewind across a shard boundary; the earlier shard has already been pruned"
+                    .to_string(),
+            );
+        }
+
+        let mut shard = self.load_active_shard()?;
+        let mut cap = self.load_cap()?;
+        let shard_ok = shard.rewind();
+        let cap_ok = cap.rewind();
+        self.save_active_shard(&shard)?;
+        self.save_cap(&cap)?;
+
+        self.active_shard_size = landing.map_or(0, |c| c.active_shard_size);
+        self.active_shard_has_marks = landing.is_some_and(|c| c.active_shard_has_marks);
+
+        self.store
+            .truncate(checkpoints.len() - 1)
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(shard_ok && cap_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled_tree(leaves: u64) -> ShardedCommitmentTree<MemoryShardStore> {
+        let mut tree = ShardedCommitmentTree::new(MemoryShardStore::new());
+        for _ in 0..leaves {
+            assert!(tree.append(MerkleHashOrchard::empty_leaf()));
+        }
+        tree
+    }
+
+    #[test]
+    fn test_finalize_prunes_a_full_shard_with_no_marks() {
+        // Fill shard 0 exactly, then append once more so the next
+        // `try_append` finalizes it before starting shard 1.
+        let mut tree = filled_tree(SHARD_SIZE);
+        assert_eq!(tree.active_shard_index, 0);
+
+        assert!(tree.append(MerkleHashOrchard::empty_leaf()));
+
+        assert_eq!(tree.active_shard_index, 1);
+        assert_eq!(tree.active_shard_size, 1);
+        match tree.store.get_shard(0).unwrap() {
+            Some(Shard::Pruned(_)) => {}
+            other => panic!("expected shard 0 to be pruned, got {:?}", other.is_some()),
+        }
+    }
+
+    #[test]
+    fn test_finalize_keeps_a_full_shard_that_holds_a_mark() {
+        // Fill all but the last slot of shard 0, append and mark the last
+        // leaf, then cross into shard 1.
+        let mut tree = filled_tree(SHARD_SIZE - 1);
+        assert!(tree.append(MerkleHashOrchard::empty_leaf()));
+        let marked_position = tree.mark().expect("mark should succeed on a just-appended leaf");
+
+        assert!(tree.append(MerkleHashOrchard::empty_leaf()));
+        assert_eq!(tree.active_shard_index, 1);
+
+        match tree.store.get_shard(0).unwrap() {
+            Some(Shard::Full(_)) => {}
+            other => panic!("expected shard 0 to stay full (it holds a mark), got {:?}", other.is_some()),
+        }
+
+        // Witnessing a position in a now-inactive, but unpruned, shard must
+        // still work.
+        let path = tree
+            .witness(marked_position, 0)
+            .expect("witness should succeed for a mark in a finalized, unpruned shard");
+        assert_eq!(path.len(), 32);
+    }
+
+    #[test]
+    fn test_rewind_refuses_to_cross_a_shard_boundary() {
+        let mut tree = filled_tree(SHARD_SIZE - 1);
+        assert!(tree.append(MerkleHashOrchard::empty_leaf()));
+        assert!(tree.checkpoint(1));
+        assert_eq!(tree.active_shard_index, 0);
+
+        // Cross into shard 1, then checkpoint again.
+        assert!(tree.append(MerkleHashOrchard::empty_leaf()));
+        assert_eq!(tree.active_shard_index, 1);
+        assert!(tree.checkpoint(2));
+
+        // The checkpoint immediately before the tip (ledger 1) was taken
+        // while shard 0 was still active; shard 0 has since been pruned, so
+        // rewinding onto that checkpoint must be refused rather than
+        // silently landing in the wrong shard.
+        let result = tree.rewind();
+        assert!(result.is_err());
+    }
+}
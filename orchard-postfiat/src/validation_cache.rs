@@ -0,0 +1,173 @@
+//! Process-global cache of bundle validation results
+//!
+//! Validating the same transaction twice - once entering the mempool, again
+//! when the block containing it arrives - re-runs the same ~1-2s Halo2
+//! proof verification. This caches a bundle's validation result keyed by a
+//! digest of its authorizing data (the proof bytes plus every per-action
+//! `spend_auth_sig` and the binding signature) together with the sighash it
+//! was checked against, so a previously-verified bundle becomes a no-op the
+//! second time.
+//!
+//! The sighash MUST be part of the key: the binding signature commits to
+//! it, so a bundle with a genuinely valid proof/signatures checked against
+//! the wrong sighash must not be treated as cached-valid for a different one.
+//!
+//! This also covers substitution of any other bundle field (nullifiers,
+//! `cmx`, the anchor, the value balance, ...) without needing them in the
+//! key directly: ZIP-244's sighash is computed over a hash of the entire
+//! transaction, which transitively commits to every field of every bundle
+//! it contains. Two bundles that differ in any such field necessarily
+//! produce different sighashes, so keying on `authorizing_data || sighash`
+//! is equivalent to keying on the full bundle contents.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Cap on the number of entries retained, evicting the oldest once exceeded
+const MAX_ENTRIES: usize = 100_000;
+
+/// BLAKE2b-256 personalization for this cache's digests, distinguishing
+/// them from any other BLAKE2b use in the stack (e.g. ZIP-244 sighashes)
+const PERSONALIZATION: &[u8; 16] = b"PostFiatOrchBVC1";
+
+struct Cache {
+    seen: HashSet<[u8; 32]>,
+    /// Insertion order, so the oldest entry can be evicted once `seen`
+    /// grows past `MAX_ENTRIES`
+    order: VecDeque<[u8; 32]>,
+}
+
+fn cache() -> &'static Mutex<Cache> {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        Mutex::new(Cache {
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        })
+    })
+}
+
+fn hits() -> &'static AtomicU64 {
+    static HITS: AtomicU64 = AtomicU64::new(0);
+    &HITS
+}
+
+fn misses() -> &'static AtomicU64 {
+    static MISSES: AtomicU64 = AtomicU64::new(0);
+    &MISSES
+}
+
+/// Compute the cache key for a bundle's authorizing data plus the sighash
+/// it's being checked against
+///
+/// `authorizing_data` is the proof bytes concatenated with every per-action
+/// `spend_auth_sig` and the binding signature, in bundle order.
+pub fn cache_key(authorizing_data: &[u8], sighash: &[u8; 32]) -> [u8; 32] {
+    let digest = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(PERSONALIZATION)
+        .to_state()
+        .update(authorizing_data)
+        .update(sighash)
+        .finalize();
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(digest.as_bytes());
+    key
+}
+
+/// Returns `true` if `key` was previously recorded as a successful
+/// verification via [`insert`]
+pub fn contains(key: &[u8; 32]) -> bool {
+    let found = cache().lock().unwrap().seen.contains(key);
+    if found {
+        hits().fetch_add(1, Ordering::Relaxed);
+    } else {
+        misses().fetch_add(1, Ordering::Relaxed);
+    }
+    found
+}
+
+/// Record `key` as a genuine successful verification, evicting the oldest
+/// entry first if the cache is at capacity
+pub fn insert(key: [u8; 32]) {
+    let mut cache = cache().lock().unwrap();
+    if cache.seen.insert(key) {
+        cache.order.push_back(key);
+        if cache.order.len() > MAX_ENTRIES {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.seen.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Current `(hits, misses)` counts, for tests and metrics
+pub fn cache_stats() -> (u64, u64) {
+    (hits().load(Ordering::Relaxed), misses().load(Ordering::Relaxed))
+}
+
+/// Clear every cached entry and reset the hit/miss counters, for tests
+pub fn clear_cache() {
+    let mut cache = cache().lock().unwrap();
+    cache.seen.clear();
+    cache.order.clear();
+    hits().store(0, Ordering::Relaxed);
+    misses().store(0, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The cache is process-global, so each test clears it first to avoid
+    // leftover state from whichever test ran before it.
+
+    #[test]
+    fn test_same_authorizing_data_different_sighash_does_not_collide() {
+        clear_cache();
+
+        let authorizing_data = b"proof-bytes || spend_auth_sigs || binding_sig";
+        let sighash_a = [1u8; 32];
+        let sighash_b = [2u8; 32];
+
+        let key_a = cache_key(authorizing_data, &sighash_a);
+        let key_b = cache_key(authorizing_data, &sighash_b);
+        assert_ne!(
+            key_a, key_b,
+            "replaying the same authorizing data against a different sighash (i.e. a different bundle/action set) must not hit the cache"
+        );
+
+        insert(key_a);
+        assert!(contains(&key_a));
+        assert!(!contains(&key_b));
+    }
+
+    #[test]
+    fn test_insert_and_contains_round_trip() {
+        clear_cache();
+
+        let key = cache_key(b"some authorizing data", &[7u8; 32]);
+        assert!(!contains(&key));
+        insert(key);
+        assert!(contains(&key));
+    }
+
+    #[test]
+    fn test_clear_cache_resets_stats_and_entries() {
+        clear_cache();
+
+        let key = cache_key(b"authorizing data", &[9u8; 32]);
+        insert(key);
+        assert!(contains(&key));
+        let (hits, _) = cache_stats();
+        assert!(hits >= 1);
+
+        clear_cache();
+        assert!(!contains(&key));
+        let (hits, misses) = cache_stats();
+        assert_eq!(hits, 0);
+        assert_eq!(misses, 1);
+    }
+}
@@ -5,25 +5,40 @@
 //! and is serialized as a single blob to disk.
 //!
 //! Key design principles from Zcash:
-//! - Use BridgeTree for automatic witness management
+//! - Use a sharded commitment tree for automatic witness management, with
+//!   memory bounded to the shards that actually hold one of our notes
 //! - Track by IncomingViewingKey (IVK), not FullViewingKey
 //! - Serialize entire state as single blob (not per-note storage)
 //! - Checkpoint at each ledger for reorg support
+//!
+//! Methods are instrumented with [`tracing`] spans at `trace` level. Trace
+//! events on the hot paths (`append_commitment`, `add_note`, `get_merkle_path`,
+//! ...) include commitments, positions, anchors, and note amounts, so enabling
+//! this wallet's trace output is unsafe in production - restrict it to a
+//! debugging session against non-sensitive data, the same posture Zcash takes
+//! with its own Orchard wallet trace logging.
 
 use orchard::{
-    keys::{FullViewingKey, IncomingViewingKey, PreparedIncomingViewingKey},
-    note::Note,
+    keys::{FullViewingKey, IncomingViewingKey, PreparedIncomingViewingKey, Scope},
+    note::{Note, RandomSeed, Rho},
     note_encryption::OrchardDomain,
     tree::{MerkleHashOrchard, MerklePath},
-    Anchor,
+    value::NoteValue,
+    Address, Anchor,
 };
 use incrementalmerkletree::{
     Position,
     Hashable,
 };
-use bridgetree::BridgeTree;
+use crate::shard_store::{MemoryShardStore, ShardedCommitmentTree};
+use crate::note_manager::{AssetId, NATIVE_ASSET};
 use zcash_note_encryption::try_note_decryption;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, trace};
+
+/// Current version of the [`OrchardWalletState`] on-disk format, written as
+/// the first byte of every serialized blob (see [`OrchardWalletState::serialize`]).
+const SERIALIZATION_VERSION: u8 = 8;
 
 /// A decrypted note with metadata for spending
 ///
@@ -31,6 +46,9 @@ use std::collections::BTreeMap;
 /// - Stores only the position, not the witness
 /// - Witness is generated on-demand via tree.witness()
 /// - Anchor is retrieved on-demand via tree.root()
+/// - A note seen in a mempool transaction can be tracked before it's mined:
+///   `position` and `ledger_seq` are `None` until the commitment is appended
+///   to the tree and [`OrchardWalletState::mark_note_mined`] is called
 #[derive(Clone, Debug)]
 pub struct DecryptedNote {
     /// The full Orchard note
@@ -41,45 +59,103 @@ pub struct DecryptedNote {
     pub nullifier: [u8; 32],
     /// Amount in drops
     pub amount: u64,
-    /// Ledger sequence where received
-    pub ledger_seq: u32,
+    /// Ledger sequence where received, or `None` if only seen unmined (mempool)
+    pub ledger_seq: Option<u32>,
     /// Transaction hash
     pub tx_hash: [u8; 32],
     /// Action index within transaction
     pub action_idx: u32,
-    /// Position in commitment tree (used to generate witness on-demand)
-    pub position: Position,
+    /// Position in commitment tree, or `None` if the note hasn't been mined
+    /// yet (used to generate witness on-demand once known)
+    pub position: Option<Position>,
     /// Anchor (Merkle root) from the transaction that created this note
     /// For reference only - actual anchor for spending comes from tree.root()
     pub anchor: Anchor,
     /// Index of the IVK that decrypted this note (index into ivks vec)
     /// Used to filter notes when spending with a specific FVK
     pub ivk_index: usize,
+    /// Ledger sequence and action index at which this note was observed
+    /// spent (its true nullifier, derived from the owning FVK, revealed by
+    /// a later bundle's action) - see
+    /// [`OrchardWalletState::try_decrypt_notes_from_bundle`]. `None` if
+    /// unspent, or if spent only via the out-of-band [`OrchardWalletState::mark_spent`]
+    /// entry point, which has no action location to record.
+    pub spent_at: Option<(u32, u32)>,
+    /// Whether this note was received on the external (payment) or internal
+    /// (change) address of the IVK that decrypted it
+    pub scope: Scope,
+    /// 512-byte plaintext memo attached to this note
+    pub memo: [u8; 512],
+    /// Asset this note carries (`NATIVE_ASSET` for plain value). See
+    /// [`crate::note_manager::AssetId`] - this crate does not yet build
+    /// against a ZSA-enabled `orchard`, so every note decrypted from a bundle
+    /// today is necessarily `NATIVE_ASSET`; the field exists so callers that
+    /// do have an `AssetBase` (e.g. a future ZSA-enabled build) have somewhere
+    /// to put it without another storage-format migration.
+    pub asset_id: AssetId,
 }
 
 /// Identifier for a note: (tx_hash, action_idx)
 type NoteId = ([u8; 32], u32);
 
+/// Outcome of scanning one bundle via
+/// [`OrchardWalletState::try_decrypt_notes_from_bundle`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BundleScanResult {
+    /// Number of new notes decrypted and added to the wallet
+    pub notes_received: usize,
+    /// Number of previously-tracked notes newly observed as spent (their
+    /// true nullifier, derived from the owning FVK, was revealed by one of
+    /// this bundle's actions)
+    pub notes_spent: usize,
+}
+
+/// Maximum number of reorg checkpoints retained, matching the depth the
+/// underlying shard and cap trees are constructed with.
+const MAX_CHECKPOINTS: usize = 100;
+
+/// Bookkeeping for one checkpoint: which notes/spends existed as of that ledger
+///
+/// The commitment tree keeps its own checkpoint stack; this tracks the
+/// wallet-level state (notes, nullifiers, spends) that the tree doesn't know
+/// about, so a rewind can restore both in lockstep.
+#[derive(Clone)]
+struct WalletCheckpoint {
+    ledger_seq: u32,
+    notes: BTreeMap<NoteId, DecryptedNote>,
+    nullifiers: BTreeMap<[u8; 32], NoteId>,
+    spent_notes: std::collections::HashSet<NoteId>,
+}
+
 /// Orchard wallet state - all data needed for spending
 ///
 /// Following Zcash's design, this structure:
 /// - Lives entirely in Rust memory
 /// - Serializes to a single blob for disk persistence
-/// - Uses BridgeTree for automatic witness computation and checkpoint management
+/// - Uses a sharded commitment tree for automatic witness computation and checkpoint management
 /// - Tracks notes by IVK, not FVK
 /// - Stores only positions, not witnesses (witnesses generated on-demand)
 pub struct OrchardWalletState {
-    /// Registered incoming viewing keys
-    /// We only track IVKs to match Zcash's design
-    ivks: Vec<IncomingViewingKey>,
+    /// Registered incoming viewing keys, tagged with which scope (external
+    /// payment address or internal change address) each one decrypts, and the
+    /// full viewing key it was derived from if registered via [`Self::add_fvk`]
+    ///
+    /// We track IVKs to match Zcash's design, but also keep the FVK when we
+    /// have it: computing a received note's own nullifier (so a later spend
+    /// of it can be recognized automatically, see
+    /// [`Self::try_decrypt_notes_from_bundle`]) requires the nullifier
+    /// deriving key, which only the FVK carries.
+    ivks: Vec<(IncomingViewingKey, Scope, Option<FullViewingKey>)>,
 
     /// Decrypted notes indexed by (tx_hash, action_idx)
     notes: BTreeMap<NoteId, DecryptedNote>,
 
-    /// BridgeTree for commitment tracking with automatic witness generation
-    /// - Depth: 32 (Orchard tree depth)
-    /// - Max checkpoints: 100 (keep last 100 ledgers for reorg support)
-    commitment_tree: BridgeTree<MerkleHashOrchard, u32, 32>,
+    /// Sharded commitment tree for commitment tracking with automatic
+    /// witness generation
+    /// - Depth: 32 (Orchard tree depth), split into shards backed by
+    ///   `MemoryShardStore` so completed shards with no marked positions can
+    ///   be pruned to just their root hash
+    commitment_tree: ShardedCommitmentTree<MemoryShardStore>,
 
     /// Nullifier tracking: nullifier -> note_id
     /// Used to mark notes as spent
@@ -95,6 +171,9 @@ pub struct OrchardWalletState {
     /// This is cleared after each bundle's notes are decrypted
     /// Needed because mark() always returns the last appended position
     cmx_to_position: BTreeMap<[u8; 32], Position>,
+
+    /// Wallet-level snapshots keyed by ledger sequence, most recent last
+    checkpoints: Vec<WalletCheckpoint>,
 }
 
 impl OrchardWalletState {
@@ -103,28 +182,60 @@ impl OrchardWalletState {
         Self {
             ivks: Vec::new(),
             notes: BTreeMap::new(),
-            commitment_tree: BridgeTree::new(100),  // Keep last 100 checkpoints for reorg support
+            commitment_tree: ShardedCommitmentTree::new(MemoryShardStore::new()),
             nullifiers: BTreeMap::new(),
             spent_notes: std::collections::HashSet::new(),
             last_checkpoint: None,
             cmx_to_position: BTreeMap::new(),
+            checkpoints: Vec::new(),
         }
     }
 
-    /// Add an incoming viewing key to track
-    pub fn add_ivk(&mut self, ivk: IncomingViewingKey) {
-        if !self.ivks.contains(&ivk) {
-            self.ivks.push(ivk);
+    /// Add an incoming viewing key to track, for the given scope
+    ///
+    /// Thin wrapper around [`Self::add_ivk_with_fvk`] for callers that only
+    /// have a bare IVK; such notes are still detected and spendable, they
+    /// just won't participate in the automatic spend detection in
+    /// [`Self::try_decrypt_notes_from_bundle`], which needs the FVK.
+    pub fn add_ivk(&mut self, ivk: IncomingViewingKey, scope: Scope) {
+        self.add_ivk_with_fvk(ivk, scope, None);
+    }
+
+    /// Add an incoming viewing key to track, for the given scope, optionally
+    /// alongside the full viewing key it was derived from
+    ///
+    /// If the same `(ivk, scope)` pair is already registered and `fvk` is
+    /// given but the stored entry has none, the stored entry is upgraded
+    /// in place rather than duplicated.
+    fn add_ivk_with_fvk(&mut self, ivk: IncomingViewingKey, scope: Scope, fvk: Option<FullViewingKey>) {
+        if let Some(existing) = self.ivks.iter_mut().find(|(k, s, _)| k == &ivk && *s == scope) {
+            if existing.2.is_none() {
+                existing.2 = fvk;
+            }
+            return;
         }
+        self.ivks.push((ivk, scope, fvk));
     }
 
-    /// Remove an incoming viewing key
+    /// Add both the external and internal IVKs derived from a full viewing
+    /// key, so notes received on the change address are tracked too
+    ///
+    /// Unlike [`Self::add_ivk`], this also retains the FVK itself alongside
+    /// each derived IVK, so [`Self::try_decrypt_notes_from_bundle`] can
+    /// compute the nullifier deriving key needed to recognize this wallet's
+    /// own notes being spent.
+    pub fn add_fvk(&mut self, fvk: &FullViewingKey) {
+        self.add_ivk_with_fvk(fvk.to_ivk(Scope::External), Scope::External, Some(fvk.clone()));
+        self.add_ivk_with_fvk(fvk.to_ivk(Scope::Internal), Scope::Internal, Some(fvk.clone()));
+    }
+
+    /// Remove an incoming viewing key (both scopes, if both are tracked)
     pub fn remove_ivk(&mut self, ivk: &IncomingViewingKey) {
-        self.ivks.retain(|k| k != ivk);
+        self.ivks.retain(|(k, _, _)| k != ivk);
     }
 
-    /// List all registered IVKs
-    pub fn list_ivks(&self) -> &[IncomingViewingKey] {
+    /// List all registered IVKs, tagged with their scope
+    pub fn list_ivks(&self) -> &[(IncomingViewingKey, Scope, Option<FullViewingKey>)] {
         &self.ivks
     }
 
@@ -136,15 +247,14 @@ impl OrchardWalletState {
     /// Following Zcash's approach:
     /// - append() adds the commitment to the tree
     /// - mark() is called separately for our notes to record position
+    #[tracing::instrument(level = "trace", skip(self))]
     pub fn append_commitment(&mut self, cmx: [u8; 32]) -> Result<(), String> {
         let cmx_hash = MerkleHashOrchard::from_bytes(&cmx)
             .into_option()
             .ok_or_else(|| "Invalid commitment bytes".to_string())?;
 
         let tree_root_before = self.commitment_tree.root(0);
-        eprintln!("\n=== Appending commitment ===");
-        eprintln!("CMX: {:?}", cmx);
-        eprintln!("Tree root BEFORE: {:?}", tree_root_before.map(|r| r.to_bytes()));
+        trace!(?tree_root_before, "appending commitment");
 
         self.commitment_tree.append(cmx_hash)
             .then_some(())
@@ -154,28 +264,35 @@ impl OrchardWalletState {
         // This is needed to track positions for multi-note bundles
         if let Some(position) = self.commitment_tree.mark() {
             self.cmx_to_position.insert(cmx, position);
-            eprintln!("Recorded position {:?} for CMX {:?}", position, cmx);
+            trace!(?position, "recorded position for commitment");
         }
 
         let tree_root_after = self.commitment_tree.root(0);
-        eprintln!("Tree root AFTER: {:?}", tree_root_after.map(|r| r.to_bytes()));
+        trace!(?tree_root_after, "appended commitment");
 
         Ok(())
     }
 
     /// Add a decrypted note to the wallet
     ///
-    /// This should be called after successfully decrypting a note that belongs to us.
-    /// The commitment must have been added to the tree first via append_commitment.
+    /// This should be called after successfully decrypting a note that belongs to us,
+    /// whether or not its transaction has been mined yet.
     ///
     /// Following Zcash's approach:
     /// - Call mark() to record the position of this note in the tree
     /// - Store only the position, not the witness
     /// - Witness will be generated on-demand when spending
     ///
+    /// If the commitment has already been added to the tree via
+    /// `append_commitment`, the note's position is taken from that mapping.
+    /// Otherwise the note is recorded as unmined (`position: None`), as when
+    /// it was seen in a mempool transaction; [`OrchardWalletState::mark_note_mined`]
+    /// assigns its position later once the transaction is included in a ledger.
+    ///
     /// # Arguments
     /// * `anchor` - The anchor from the transaction that created this note.
     ///              For reference only - actual anchor comes from tree.root()
+    #[tracing::instrument(level = "trace", skip(self, note, anchor, scope, memo))]
     pub fn add_note(
         &mut self,
         note: Note,
@@ -183,26 +300,28 @@ impl OrchardWalletState {
         nullifier: [u8; 32],
         tx_hash: [u8; 32],
         action_idx: u32,
-        ledger_seq: u32,
+        ledger_seq: Option<u32>,
         anchor: Anchor,
         ivk_index: usize,
+        scope: Scope,
+        memo: [u8; 512],
+        asset_id: AssetId,
     ) -> Result<(), String> {
         let amount = note.value().inner();
 
-        // Get the position from the mapping that was created during append_commitment
-        // This ensures each note gets its correct individual position
-        let position = self.cmx_to_position.get(&cmx)
-            .copied()
-            .ok_or_else(|| format!("Position not found for CMX {:?}. Did you forget to call append_commitment first?", cmx))?;
+        // If the commitment was appended already, the mapping created during
+        // append_commitment has this note's position; otherwise it's unmined.
+        let position = self.cmx_to_position.get(&cmx).copied();
 
-        eprintln!("wallet_state::add_note: Using position {:?} for note (from cmx mapping)", position);
+        trace!(?position, "using position from cmx mapping");
 
         // Get current tree root for comparison
         if let Some(current_root) = self.commitment_tree.root(0) {
-            eprintln!("wallet_state::add_note: Current tree root (depth 0): {:?}",
-                      current_root.to_bytes());
-            eprintln!("wallet_state::add_note: Bundle anchor (historical, stored for reference): {:?}",
-                      anchor.to_bytes());
+            trace!(
+                current_root = ?current_root.to_bytes(),
+                bundle_anchor = ?anchor.to_bytes(),
+                "comparing current tree root to bundle anchor (stored for reference)",
+            );
         }
 
         let note_id = (tx_hash, action_idx);
@@ -218,6 +337,10 @@ impl OrchardWalletState {
             position,
             anchor,  // Store for reference only
             ivk_index,
+            spent_at: None,
+            scope,
+            memo,
+            asset_id,
         };
 
         // Store the note
@@ -226,8 +349,32 @@ impl OrchardWalletState {
         // Track nullifier
         self.nullifiers.insert(nullifier, note_id);
 
-        eprintln!("wallet_state::add_note: Successfully stored note with amount {} at position {:?}",
-                  amount, position);
+        debug!(amount, ?position, "stored note");
+
+        Ok(())
+    }
+
+    /// Assign a tree position and ledger height to a previously-unmined note
+    ///
+    /// Call this once a note added via `add_note` with no position (because
+    /// it was only seen in a mempool transaction) has its commitment appended
+    /// to the tree, i.e. once its transaction is mined. The commitment must
+    /// have been appended via `append_commitment` first, in the same way a
+    /// freshly-mined note's position is looked up.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub fn mark_note_mined(&mut self, cmx: &[u8; 32], ledger_seq: u32) -> Result<(), String> {
+        let position = self.cmx_to_position.get(cmx)
+            .copied()
+            .ok_or_else(|| format!("Position not found for CMX {:?}. Did you forget to call append_commitment first?", cmx))?;
+
+        let note = self.notes.values_mut()
+            .find(|note| &note.cmx == cmx)
+            .ok_or_else(|| "No tracked note with this commitment".to_string())?;
+
+        note.position = Some(position);
+        note.ledger_seq = Some(ledger_seq);
+
+        debug!(?position, ledger_seq, "marked note as mined");
 
         Ok(())
     }
@@ -260,29 +407,67 @@ impl OrchardWalletState {
 
     /// Try to decrypt and add notes from an Orchard bundle
     ///
-    /// This attempts decryption of all actions in the bundle with registered IVKs.
-    /// If any note decrypts successfully, it's added to the wallet.
+    /// This attempts decryption of all actions in the bundle with every
+    /// registered IVK, external and internal alike - a change output sent
+    /// back to this wallet's own internal (change) address is recovered the
+    /// same way a payment to its external address is, as long as both were
+    /// registered via [`Self::add_fvk`] (a bare [`Self::add_ivk`] only covers
+    /// whichever single scope it was given).
+    ///
+    /// Also detects this wallet's own notes being spent: for every tracked
+    /// note whose owning IVK was registered via [`Self::add_fvk`] (and so has
+    /// a full viewing key attached), this computes the note's true nullifier
+    /// and checks it against every action's revealed nullifier in the same
+    /// pass used for decryption, marking matches spent and recording the
+    /// spending `ledger_seq`/action index on the note so a later
+    /// [`Self::rewind`] can reverse it.
     ///
-    /// Returns the number of notes successfully decrypted and added.
+    /// Returns the counts of newly received and newly detected-spent notes.
     pub fn try_decrypt_notes_from_bundle<V>(
         &mut self,
         bundle: &orchard::Bundle<orchard::bundle::Authorized, V>,
         tx_hash: [u8; 32],
         ledger_seq: u32,
-    ) -> Result<usize, String> {
+    ) -> Result<BundleScanResult, String> {
         let mut decrypted_count = 0;
+        let mut spent_count = 0;
+
+        // Map each of our own unspent notes' true nullifier (derived from the
+        // FVK that owns it) to its note_id, so a spend revealed in this
+        // bundle can be recognized below. Notes tracked via a bare add_ivk
+        // have no FVK on file and so have no nullifier deriving key - they
+        // can't be matched here and still need an external mark_spent call.
+        let nullifier_to_note: BTreeMap<[u8; 32], NoteId> = self
+            .notes
+            .iter()
+            .filter(|(note_id, _)| !self.spent_notes.contains(*note_id))
+            .filter_map(|(note_id, note)| {
+                let fvk = self.ivks.get(note.ivk_index)?.2.as_ref()?;
+                Some((note.note.nullifier(fvk).to_bytes(), *note_id))
+            })
+            .collect();
 
         // Iterate over all actions in the bundle
         for (action_idx, action) in bundle.actions().iter().enumerate() {
+            // Check whether this action spends one of our own tracked notes
+            let revealed_nullifier = action.nullifier().to_bytes();
+            if let Some(&note_id) = nullifier_to_note.get(&revealed_nullifier) {
+                self.spent_notes.insert(note_id);
+                if let Some(note) = self.notes.get_mut(&note_id) {
+                    note.spent_at = Some((ledger_seq, action_idx as u32));
+                }
+                spent_count += 1;
+            }
+
             // Try each registered IVK
-            for (ivk_index, ivk) in self.ivks.iter().enumerate() {
+            for (ivk_index, (ivk, scope, _fvk)) in self.ivks.iter().enumerate() {
                 // Prepare IVK for decryption
                 let prepared_ivk = PreparedIncomingViewingKey::new(ivk);
 
                 // Try to decrypt the note
                 let domain = OrchardDomain::for_action(action);
 
-                if let Some((note, _recipient, _memo)) = try_note_decryption(&domain, &prepared_ivk, action) {
+                if let Some((note, _recipient, memo)) = try_note_decryption(&domain, &prepared_ivk, action) {
                     // Successfully decrypted! Extract the data we need
 
                     // Get commitment (cmx)
@@ -313,9 +498,12 @@ impl OrchardWalletState {
                         nullifier,
                         tx_hash,
                         action_idx as u32,
-                        ledger_seq,
+                        Some(ledger_seq),
                         current_anchor,  // Use CURRENT tree anchor (after all commitments added)
                         ivk_index,
+                        *scope,
+                        memo,
+                        NATIVE_ASSET,
                     )?;
 
                     decrypted_count += 1;
@@ -329,6 +517,100 @@ impl OrchardWalletState {
         // Clear the cmx->position mapping now that we're done processing this bundle
         self.cmx_to_position.clear();
 
+        Ok(BundleScanResult {
+            notes_received: decrypted_count,
+            notes_spent: spent_count,
+        })
+    }
+
+    /// Try to decrypt and add notes from many Orchard bundles in a single pass
+    ///
+    /// Scanning a ledger range one bundle at a time via
+    /// [`try_decrypt_notes_from_bundle`](Self::try_decrypt_notes_from_bundle) means
+    /// one full trial decryption per (action, registered IVK) pair. This instead
+    /// flattens every action across every supplied bundle into one list and runs
+    /// [`zcash_note_encryption::batch::try_note_decryption`] over the whole set
+    /// with all prepared IVKs at once, which amortizes the expensive Diffie-Hellman
+    /// key agreement shared across the batch.
+    ///
+    /// Note: unlike a wire-format ledger scanner, every action reaching this crate
+    /// is already a full `orchard::Action` (this crate never constructs Orchard's
+    /// lightweight `CompactAction`), so the batch call below recovers the full note
+    /// and memo directly - there's no separate "memo-bearing" fallback path needed.
+    ///
+    /// `bundles` is `(bundle, ledger_seq, tx_hash)` triples. Returns the total
+    /// number of notes successfully decrypted and added across all of them.
+    pub fn batch_decrypt_notes<V>(
+        &mut self,
+        bundles: &[(&orchard::Bundle<orchard::bundle::Authorized, V>, u32, [u8; 32])],
+    ) -> Result<usize, String> {
+        let prepared_ivks: Vec<PreparedIncomingViewingKey> = self
+            .ivks
+            .iter()
+            .map(|(ivk, _scope, _fvk)| PreparedIncomingViewingKey::new(ivk))
+            .collect();
+
+        // Flatten every action across every bundle, remembering which bundle and
+        // action index each entry came from so a hit can be routed back to the
+        // right tx_hash/ledger_seq/action_idx.
+        let mut domains_and_actions = Vec::new();
+        let mut origins = Vec::new();
+        for (bundle_idx, (bundle, ledger_seq, tx_hash)) in bundles.iter().enumerate() {
+            for (action_idx, action) in bundle.actions().iter().enumerate() {
+                let domain = OrchardDomain::for_action(action);
+                domains_and_actions.push((domain, action.clone()));
+                origins.push((bundle_idx, action_idx, *ledger_seq, *tx_hash));
+            }
+        }
+
+        if domains_and_actions.is_empty() {
+            return Ok(0);
+        }
+
+        let results = zcash_note_encryption::batch::try_note_decryption(&prepared_ivks, &domains_and_actions);
+
+        let mut decrypted_count = 0;
+        for (result, (bundle_idx, action_idx, ledger_seq, tx_hash)) in
+            results.into_iter().zip(origins.into_iter())
+        {
+            let Some((note, _recipient, memo, ivk_index)) = result else {
+                continue;
+            };
+
+            let (bundle, _, _) = bundles[bundle_idx];
+            let action = &bundle.actions()[action_idx];
+            let scope = self.ivks[ivk_index].1;
+
+            let cmx = action.cmx().to_bytes();
+            let nullifier = action.nullifier().to_bytes();
+
+            // See try_decrypt_notes_from_bundle: newly created notes must record
+            // the tree state after all of this scan's commitments were appended.
+            let current_tree_root = self.commitment_tree.root(0)
+                .ok_or_else(|| "Cannot get current tree root".to_string())?;
+            let current_anchor = Anchor::from_bytes(current_tree_root.to_bytes())
+                .into_option()
+                .ok_or_else(|| "Failed to create anchor from tree root".to_string())?;
+
+            self.add_note(
+                note,
+                cmx,
+                nullifier,
+                tx_hash,
+                action_idx as u32,
+                Some(ledger_seq),
+                current_anchor,
+                ivk_index,
+                scope,
+                memo,
+                NATIVE_ASSET,
+            )?;
+
+            decrypted_count += 1;
+        }
+
+        self.cmx_to_position.clear();
+
         Ok(decrypted_count)
     }
 
@@ -349,15 +631,116 @@ impl OrchardWalletState {
             .ok_or_else(|| "Failed to create anchor".to_string())
     }
 
-    /// Get total balance (unspent notes only)
+    /// Get total native-asset balance (unspent, mined notes only)
+    ///
+    /// Thin wrapper around [`OrchardWalletState::get_balance_with_pending`]
+    /// that excludes unmined (mempool) receives.
     pub fn get_balance(&self) -> u64 {
+        self.get_balance_with_pending(false)
+    }
+
+    /// Get total native-asset balance, optionally including unmined (mempool) receives
+    ///
+    /// Thin wrapper around [`OrchardWalletState::get_asset_balance`] for
+    /// [`NATIVE_ASSET`]. With `include_pending`, notes with no tree position
+    /// yet count toward the balance; otherwise only mined, unspent notes are
+    /// summed.
+    pub fn get_balance_with_pending(&self, include_pending: bool) -> u64 {
+        self.get_asset_balance(NATIVE_ASSET, include_pending)
+    }
+
+    /// Get total balance of a specific asset, optionally including unmined
+    /// (mempool) receives
+    ///
+    /// See [`DecryptedNote::asset_id`] - every note decrypted by this build
+    /// carries [`NATIVE_ASSET`], so this only returns a non-zero balance for
+    /// other asset ids once notes are added with them some other way.
+    pub fn get_asset_balance(&self, asset_id: AssetId, include_pending: bool) -> u64 {
         self.notes
             .iter()
-            .filter(|(id, _)| !self.spent_notes.contains(id))
+            .filter(|(id, note)| {
+                !self.spent_notes.contains(id)
+                    && (include_pending || note.position.is_some())
+                    && note.asset_id == asset_id
+            })
             .map(|(_, note)| note.amount)
             .sum()
     }
 
+    /// Get total native-asset balance of unspent notes meeting a confirmation threshold
+    ///
+    /// Thin wrapper around [`OrchardWalletState::get_asset_balance_with_confirmations`]
+    /// for [`NATIVE_ASSET`].
+    pub fn get_balance_with_confirmations(&self, min_confirmations: u32, chain_tip_seq: u32) -> u64 {
+        self.get_asset_balance_with_confirmations(NATIVE_ASSET, min_confirmations, chain_tip_seq)
+    }
+
+    /// Get total balance of a specific asset, counting only notes meeting a
+    /// confirmation threshold
+    ///
+    /// `min_confirmations == 0` includes notes only seen in the mempool (no
+    /// `ledger_seq` yet). Otherwise a note counts once
+    /// `chain_tip_seq - note.ledger_seq + 1 >= min_confirmations` - a note
+    /// mined in the current tip ledger has 1 confirmation, matching Zcash's
+    /// own `z_getbalance`/`z_getbalanceforaccount` confirmation counting.
+    pub fn get_asset_balance_with_confirmations(
+        &self,
+        asset_id: AssetId,
+        min_confirmations: u32,
+        chain_tip_seq: u32,
+    ) -> u64 {
+        self.notes
+            .iter()
+            .filter(|(id, note)| !self.spent_notes.contains(id) && note.asset_id == asset_id)
+            .filter(|(_, note)| Self::meets_confirmations(note.ledger_seq, min_confirmations, chain_tip_seq))
+            .map(|(_, note)| note.amount)
+            .sum()
+    }
+
+    /// Per-asset balance breakdown, counting only notes meeting a
+    /// confirmation threshold - the way `z_getbalanceforaccount` reports
+    /// per-pool totals
+    pub fn get_balance_breakdown_with_confirmations(
+        &self,
+        min_confirmations: u32,
+        chain_tip_seq: u32,
+    ) -> HashMap<AssetId, u64> {
+        let mut balances = HashMap::new();
+        for (id, note) in self.notes.iter() {
+            if self.spent_notes.contains(id) {
+                continue;
+            }
+            if !Self::meets_confirmations(note.ledger_seq, min_confirmations, chain_tip_seq) {
+                continue;
+            }
+            *balances.entry(note.asset_id).or_insert(0) += note.amount;
+        }
+        balances
+    }
+
+    /// Whether a note received at `ledger_seq` meets `min_confirmations` as of `chain_tip_seq`
+    fn meets_confirmations(ledger_seq: Option<u32>, min_confirmations: u32, chain_tip_seq: u32) -> bool {
+        if min_confirmations == 0 {
+            return true;
+        }
+        match ledger_seq {
+            None => false,
+            Some(seq) => chain_tip_seq.saturating_sub(seq).saturating_add(1) >= min_confirmations,
+        }
+    }
+
+    /// Enumerate the distinct assets currently held (unspent, mined notes only)
+    pub fn list_asset_ids(&self) -> Vec<AssetId> {
+        let mut ids: Vec<AssetId> = self.notes
+            .iter()
+            .filter(|(id, note)| !self.spent_notes.contains(id) && note.position.is_some())
+            .map(|(_, note)| note.asset_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
     /// Get all notes (optionally include spent)
     pub fn list_notes(&self, include_spent: bool) -> Vec<&DecryptedNote> {
         self.notes
@@ -375,13 +758,20 @@ impl OrchardWalletState {
             .map(|(_, note)| note)
     }
 
-    /// Get spendable notes (unspent notes with witnesses)
+    /// Get the plaintext memo attached to a note, by commitment
+    pub fn get_memo(&self, cmx: &[u8; 32]) -> Option<&[u8; 512]> {
+        self.get_note(cmx).map(|note| &note.memo)
+    }
+
+    /// Get spendable notes (unspent, mined notes with witnesses)
     ///
+    /// Notes with no tree position yet (seen in a mempool transaction but not
+    /// mined) are excluded, since no witness can be generated for them.
     /// Returns notes sorted by amount (smallest first) for coin selection
     pub fn get_spendable_notes(&self) -> Vec<&DecryptedNote> {
         let mut spendable: Vec<&DecryptedNote> = self.notes
             .iter()
-            .filter(|(id, _)| !self.spent_notes.contains(id))
+            .filter(|(id, note)| !self.spent_notes.contains(id) && note.position.is_some())
             .map(|(_, note)| note)
             .collect();
 
@@ -391,37 +781,71 @@ impl OrchardWalletState {
         spendable
     }
 
+    /// Get spendable notes belonging to a specific FVK, optionally restricted
+    /// to one scope (external payment address or internal change address)
+    ///
+    /// If `scope` is `None`, notes from both the external and internal IVKs
+    /// derived from `fvk` are returned, so change notes are spendable
+    /// alongside received notes.
+    pub fn get_spendable_notes_for_fvk(&self, fvk: &FullViewingKey, scope: Option<Scope>) -> Vec<&DecryptedNote> {
+        let ivk_indices = self.ivk_indices_for_fvk(fvk, scope);
+
+        let mut spendable = self.get_spendable_notes();
+        spendable.retain(|note| ivk_indices.contains(&note.ivk_index));
+        spendable
+    }
+
+    /// Indices into `self.ivks` of the IVKs derived from `fvk`, optionally
+    /// restricted to a single scope
+    fn ivk_indices_for_fvk(&self, fvk: &FullViewingKey, scope: Option<Scope>) -> Vec<usize> {
+        [Scope::External, Scope::Internal]
+            .into_iter()
+            .filter(|s| scope.map_or(true, |wanted| wanted == *s))
+            .filter_map(|s| {
+                let ivk = fvk.to_ivk(s);
+                self.ivks.iter().position(|(stored_ivk, stored_scope, _)| stored_ivk == &ivk && *stored_scope == s)
+            })
+            .collect()
+    }
+
     /// Get Merkle path for a note (for spending)
     ///
-    /// Generates the witness on-demand using BridgeTree.witness()
+    /// Generates the witness on-demand using ShardedCommitmentTree::witness()
     /// Following Zcash's approach: witness(position, checkpoint_depth)
     ///
     /// IMPORTANT: The witness must be generated from the CURRENT tree (depth 0)
     /// but the ANCHOR comes from the previous checkpoint (depth 1).
     /// The witness authenticates the note's position in the tree.
     /// The anchor authenticates the tree state.
+    #[tracing::instrument(level = "trace", skip(self, note))]
     pub fn get_merkle_path(&self, note: &DecryptedNote) -> Result<MerklePath, String> {
-        // Generate witness on-demand from BridgeTree
+        let (position_u32, auth_path) = self.get_witness_parts(note)?;
+        Ok(MerklePath::from_parts(position_u32, auth_path))
+    }
+
+    /// Raw `(position, auth_path)` components of a note's current witness
+    ///
+    /// Split out from [`get_merkle_path`](Self::get_merkle_path) so callers that
+    /// need to serialize the witness (rather than feed it straight to the
+    /// Orchard builder) aren't forced to pick apart a `MerklePath`.
+    pub fn get_witness_parts(&self, note: &DecryptedNote) -> Result<(u32, [MerkleHashOrchard; 32]), String> {
+        let position = note.position
+            .ok_or_else(|| "Note has no tree position yet (not mined)".to_string())?;
+
+        // Generate witness on-demand from the sharded commitment tree
         // checkpoint_depth = 0 means current tree state (includes all commitments)
-        eprintln!("\n=== wallet_state::get_merkle_path ===");
-        eprintln!("Generating witness for position {:?} at checkpoint depth 0", note.position);
+        trace!(?position, "generating witness at checkpoint depth 0");
 
-        let auth_path_vec = self.commitment_tree.witness(note.position, 0)
+        let auth_path_vec = self.commitment_tree.witness(position, 0)
             .map_err(|e| format!(
                 "Failed to generate witness for position {:?} at checkpoint depth 0: {:?}",
-                note.position, e
+                position, e
             ))?;
 
-        eprintln!("Auth path length: {}", auth_path_vec.len());
-
-        // Calculate what root this witness authenticates to
-        // This is done by hashing up from the note's commitment through the auth path
-        let witness_root = self.commitment_tree.root(0)
-            .ok_or_else(|| "Cannot get tree root at depth 0".to_string())?;
-        eprintln!("Witness authenticates to tree root (depth 0): {:?}", witness_root.to_bytes());
+        trace!(auth_path_len = auth_path_vec.len(), "generated witness");
 
         // Convert position to u32 for Orchard's MerklePath
-        let position_u32: u32 = u64::from(note.position).try_into()
+        let position_u32: u32 = u64::from(position).try_into()
             .map_err(|_| "Position too large for u32".to_string())?;
 
         // Convert to fixed-size array
@@ -430,8 +854,7 @@ impl OrchardWalletState {
             auth_path[i] = *elem;
         }
 
-        // Convert to Orchard's MerklePath
-        Ok(MerklePath::from_parts(position_u32, auth_path))
+        Ok((position_u32, auth_path))
     }
 
     /// Get the anchor (Merkle root) that must be used when spending this note
@@ -440,20 +863,24 @@ impl OrchardWalletState {
     /// - Always use checkpoint_depth = 0 (current tree state)
     /// - This gives the most recent tree root
     /// - This anchor MUST exist in the ledger's anchor table
+    #[tracing::instrument(level = "trace", skip(self, note))]
     pub fn get_note_anchor(&self, note: &DecryptedNote) -> Result<Anchor, String> {
-        eprintln!("\n=== wallet_state::get_note_anchor ===");
-        eprintln!("Getting anchor for note at position {:?}", note.position);
+        trace!(position = ?note.position, "getting anchor for note");
 
         // Get anchor from CURRENT tree state (depth 0), matching Zcash
         let tree_root = self.commitment_tree.root(0)
             .ok_or_else(|| "Tree is empty, no anchor available".to_string())?;
 
-        eprintln!("Tree root at depth 0 (current tree): {:?}", tree_root.to_bytes());
-        eprintln!("Note's stored anchor (from tx that created it): {:?}", note.anchor.to_bytes());
+        trace!(
+            current_tree_root = ?tree_root.to_bytes(),
+            note_anchor = ?note.anchor.to_bytes(),
+            "comparing current tree root to note's stored anchor",
+        );
 
         if tree_root.to_bytes() != note.anchor.to_bytes() {
-            eprintln!("WARNING: Current tree root != note's stored anchor!");
-            eprintln!("  This is expected if other notes were added after this note was created");
+            // Expected if other notes were added after this note was created -
+            // the note's stored anchor is historical, not the current tip.
+            debug!("current tree root differs from note's stored anchor");
         }
 
         Anchor::from_bytes(tree_root.to_bytes())
@@ -463,49 +890,67 @@ impl OrchardWalletState {
 
     /// Select notes for spending a given amount
     ///
+    /// Thin wrapper around [`OrchardWalletState::select_notes_with_scope`]
+    /// that, if `fvk` is given, selects from both its external and internal
+    /// (change) notes.
+    pub fn select_notes(&self, target_amount: u64, fvk: Option<&FullViewingKey>) -> Result<Vec<&DecryptedNote>, String> {
+        self.select_notes_with_scope(target_amount, fvk, None)
+    }
+
+    /// Select notes for spending a given amount, optionally restricted to
+    /// one scope of a specific FVK
+    ///
     /// Returns notes that sum to at least the target amount.
     /// Uses a greedy algorithm (smallest notes first).
     ///
-    /// If `fvk` is provided, only selects notes belonging to that FVK.
-    pub fn select_notes(&self, target_amount: u64, fvk: Option<&FullViewingKey>) -> Result<Vec<&DecryptedNote>, String> {
+    /// If `fvk` is provided, only selects notes belonging to that FVK. If
+    /// `scope` is also provided, only notes received on that scope (external
+    /// payment address or internal change address) are considered;
+    /// otherwise both are eligible. `scope` without `fvk` is ignored.
+    #[tracing::instrument(level = "trace", skip(self, fvk, scope))]
+    pub fn select_notes_with_scope(
+        &self,
+        target_amount: u64,
+        fvk: Option<&FullViewingKey>,
+        scope: Option<Scope>,
+    ) -> Result<Vec<&DecryptedNote>, String> {
         let mut spendable = self.get_spendable_notes();
 
         // If FVK is provided, filter notes by ivk_index
         if let Some(fvk) = fvk {
-            let ivk = fvk.to_ivk(orchard::keys::Scope::External);
-
-            // Find which IVK index this matches
-            let ivk_index = self.ivks.iter().position(|stored_ivk| stored_ivk == &ivk)
-                .ok_or_else(|| "FVK not found in wallet".to_string())?;
+            let ivk_indices = self.ivk_indices_for_fvk(fvk, scope);
+            if ivk_indices.is_empty() {
+                return Err("FVK not found in wallet".to_string());
+            }
 
-            eprintln!("wallet_state::select_notes: Filtering notes for ivk_index={}", ivk_index);
+            trace!(?ivk_indices, "filtering notes by FVK's IVK indices");
 
-            // Filter to only notes from this IVK
-            spendable.retain(|note| note.ivk_index == ivk_index);
+            // Filter to only notes from this FVK's IVK(s)
+            spendable.retain(|note| ivk_indices.contains(&note.ivk_index));
         }
 
-        eprintln!("wallet_state::select_notes: Called with target_amount={}", target_amount);
-        eprintln!("wallet_state::select_notes: Found {} spendable notes{}",
-                  spendable.len(),
-                  if fvk.is_some() { " (filtered by FVK)" } else { "" });
+        trace!(
+            spendable_count = spendable.len(),
+            filtered_by_fvk = fvk.is_some(),
+            "selecting notes",
+        );
 
         let mut selected = Vec::new();
         let mut total = 0u64;
 
-        for (idx, note) in spendable.into_iter().enumerate() {
-            eprintln!("wallet_state::select_notes: Considering note {} - amount: {}, cmx: {:?}, ivk_index: {}",
-                      idx, note.amount, note.cmx, note.ivk_index);
+        for note in spendable {
+            trace!(amount = note.amount, cmx = ?note.cmx, ivk_index = note.ivk_index, "considering note");
             selected.push(note);
             total = total.checked_add(note.amount)
                 .ok_or_else(|| "Amount overflow".to_string())?;
 
             if total >= target_amount {
-                eprintln!("wallet_state::select_notes: Selected {} notes with total={}", selected.len(), total);
+                debug!(selected_count = selected.len(), total, "selected notes");
                 return Ok(selected);
             }
         }
 
-        eprintln!("wallet_state::select_notes: Insufficient balance - have {}, need {}", total, target_amount);
+        debug!(total, target_amount, "insufficient balance");
         Err(format!(
             "Insufficient balance: have {}, need {}",
             total, target_amount
@@ -518,13 +963,37 @@ impl OrchardWalletState {
     /// - Call tree.checkpoint() to save the current tree state
     /// - This creates a checkpoint that can be used for witness generation
     /// - Allows reorg support by keeping historical tree states
+    ///
+    /// `ledger_seq` must be exactly `last_checkpoint + 1` (or any value for
+    /// the first checkpoint). Checkpoint depth only maps deterministically
+    /// to ledger height, which witness and anchor retrieval both depend on,
+    /// if ledgers are checkpointed exactly once and in sequential order.
+    #[tracing::instrument(level = "trace", skip(self))]
     pub fn checkpoint(&mut self, ledger_seq: u32) -> bool {
+        if let Some(last) = self.last_checkpoint {
+            if ledger_seq != last + 1 {
+                debug!(ledger_seq, expected = last + 1, "rejected out-of-order checkpoint");
+                return false;
+            }
+        }
+
         let success = self.commitment_tree.checkpoint(ledger_seq);
         if success {
             self.last_checkpoint = Some(ledger_seq);
-            eprintln!("wallet_state::checkpoint: Created checkpoint at ledger {}", ledger_seq);
+
+            self.checkpoints.push(WalletCheckpoint {
+                ledger_seq,
+                notes: self.notes.clone(),
+                nullifiers: self.nullifiers.clone(),
+                spent_notes: self.spent_notes.clone(),
+            });
+            if self.checkpoints.len() > MAX_CHECKPOINTS {
+                self.checkpoints.remove(0);
+            }
+
+            trace!(ledger_seq, "created checkpoint");
         } else {
-            eprintln!("wallet_state::checkpoint: WARNING - Failed to create checkpoint at ledger {}", ledger_seq);
+            debug!(ledger_seq, "failed to create checkpoint");
         }
         success
     }
@@ -534,14 +1003,443 @@ impl OrchardWalletState {
         self.last_checkpoint
     }
 
+    /// Roll the wallet back to a previously recorded checkpoint
+    ///
+    /// This undoes everything observed after `to_ledger_seq`: notes received
+    /// later are removed, their nullifier entries are dropped, and spends
+    /// only observed later are un-marked. Used to recover from a chain
+    /// reorganization without a full rescan.
+    pub fn rewind(&mut self, to_ledger_seq: u32) -> Result<(), String> {
+        let idx = self.checkpoints.iter()
+            .position(|c| c.ledger_seq == to_ledger_seq)
+            .ok_or_else(|| format!("No checkpoint recorded at ledger {}", to_ledger_seq))?;
+
+        // Truncate the commitment tree back to the checkpoint at `to_ledger_seq`,
+        // one checkpoint at a time via the commitment tree's rewind API.
+        while self.checkpoints.len() > idx + 1 {
+            self.commitment_tree.rewind()?
+                .then_some(())
+                .ok_or_else(|| "Failed to rewind commitment tree".to_string())?;
+            self.checkpoints.pop();
+        }
+
+        let state = self.checkpoints[idx].clone();
+        self.notes = state.notes;
+        self.nullifiers = state.nullifiers;
+        self.spent_notes = state.spent_notes;
+        self.last_checkpoint = Some(to_ledger_seq);
+        self.cmx_to_position.clear();
+
+        Ok(())
+    }
+
+    /// Roll the wallet back to the most recent checkpoint at or before
+    /// `target_ledger_seq`
+    ///
+    /// Thin wrapper around [`OrchardWalletState::rewind`] for the common
+    /// reorg case, where `target_ledger_seq` is the ledger the chain reorged
+    /// to but checkpointing lags the tip, so that exact height may not have
+    /// been checkpointed yet. Returns the ledger_seq actually rewound to, or
+    /// an error if `target_ledger_seq` predates the oldest retained
+    /// checkpoint.
+    pub fn rewind_to_or_before(&mut self, target_ledger_seq: u32) -> Result<u32, String> {
+        let ledger_seq = self.checkpoints.iter()
+            .rev()
+            .map(|c| c.ledger_seq)
+            .find(|&seq| seq <= target_ledger_seq)
+            .ok_or_else(|| format!(
+                "No checkpoint at or before ledger {} (oldest retained checkpoint is {:?})",
+                target_ledger_seq,
+                self.checkpoints.first().map(|c| c.ledger_seq),
+            ))?;
+
+        self.rewind(ledger_seq)?;
+        Ok(ledger_seq)
+    }
+
     /// Reset wallet state (for testing)
     pub fn reset(&mut self) {
         self.notes.clear();
-        self.commitment_tree = BridgeTree::new(100);  // Keep last 100 checkpoints
+        self.commitment_tree = ShardedCommitmentTree::new(MemoryShardStore::new());
         self.nullifiers.clear();
         self.spent_notes.clear();
         self.last_checkpoint = None;
         self.cmx_to_position.clear();
+        self.checkpoints.clear();
+    }
+
+    /// Serialize the entire wallet state as a single versioned blob
+    ///
+    /// Following Zcash's approach of persisting the witness tree together
+    /// with note positions as one unit, this writes the `ivks` (each tagged
+    /// with the FVK it was derived from, if any), every `DecryptedNote`
+    /// (including its `position`, so witnesses remain generatable after a
+    /// reload - unmined notes round-trip with `position` and `ledger_seq`
+    /// still `None`), the spent-note set, `last_checkpoint`, the commitment
+    /// tree itself (which carries its own internal checkpoint/frontier
+    /// history), and every wallet-level `checkpoints` snapshot (so
+    /// [`Self::rewind`] keeps working across a restart, not just before one).
+    /// The `nullifiers` index is not stored directly, at either the top level
+    /// or within a checkpoint snapshot - it's rebuilt from the accompanying
+    /// notes on deserialize, since it's fully derived from them.
+    /// `cmx_to_position` is scratch state scoped to a single in-flight bundle
+    /// and is never persisted.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.push(SERIALIZATION_VERSION);
+
+        buf.extend_from_slice(&(self.ivks.len() as u32).to_le_bytes());
+        for (ivk, scope, fvk) in &self.ivks {
+            buf.extend_from_slice(&ivk.to_bytes());
+            buf.push(scope_to_byte(*scope));
+            match fvk {
+                Some(fvk) => {
+                    buf.push(1);
+                    buf.extend_from_slice(&fvk.to_bytes());
+                }
+                None => buf.push(0),
+            }
+        }
+
+        let tree_json = serde_json::to_vec(&self.commitment_tree)
+            .expect("commitment tree serialization is infallible");
+        buf.extend_from_slice(&(tree_json.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&tree_json);
+
+        write_notes(&mut buf, &self.notes);
+        write_spent_notes(&mut buf, &self.spent_notes);
+
+        match self.last_checkpoint {
+            Some(seq) => {
+                buf.push(1);
+                buf.extend_from_slice(&seq.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+
+        buf.extend_from_slice(&(self.checkpoints.len() as u32).to_le_bytes());
+        for checkpoint in &self.checkpoints {
+            buf.extend_from_slice(&checkpoint.ledger_seq.to_le_bytes());
+            write_notes(&mut buf, &checkpoint.notes);
+            write_spent_notes(&mut buf, &checkpoint.spent_notes);
+        }
+
+        buf
+    }
+
+    /// Deserialize a wallet state previously produced by [`OrchardWalletState::serialize`]
+    ///
+    /// Every mined note's witness is regenerated from the restored
+    /// commitment tree and checked to still authenticate to the tree's root,
+    /// so a truncated or otherwise corrupted blob is rejected here rather
+    /// than silently producing a wallet that can't build valid spend proofs.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = ByteReader::new(bytes);
+
+        let version = r.read_u8()?;
+        if version != SERIALIZATION_VERSION {
+            return Err(format!("Unsupported OrchardWalletState serialization version {}", version));
+        }
+
+        let ivk_count = r.read_u32()?;
+        let mut ivks = Vec::with_capacity(ivk_count as usize);
+        for _ in 0..ivk_count {
+            let ivk_bytes = r.read_array::<64>()?;
+            let ivk = IncomingViewingKey::from_bytes(&ivk_bytes)
+                .into_option()
+                .ok_or_else(|| "Invalid incoming viewing key".to_string())?;
+            let scope = scope_from_byte(r.read_u8()?)?;
+            let fvk = match r.read_u8()? {
+                0 => None,
+                _ => {
+                    let fvk_bytes = r.read_array::<96>()?;
+                    Some(FullViewingKey::from_bytes(&fvk_bytes)
+                        .ok_or_else(|| "Invalid full viewing key".to_string())?)
+                }
+            };
+            ivks.push((ivk, scope, fvk));
+        }
+
+        let tree_len = r.read_u64()? as usize;
+        let tree_json = r.read_bytes(tree_len)?;
+        let commitment_tree: ShardedCommitmentTree<MemoryShardStore> = serde_json::from_slice(tree_json)
+            .map_err(|e| format!("Failed to deserialize commitment tree: {}", e))?;
+
+        let notes = read_notes(&mut r)?;
+        let nullifiers = rebuild_nullifiers(&notes);
+        let spent_notes = read_spent_notes(&mut r)?;
+
+        let last_checkpoint = match r.read_u8()? {
+            0 => None,
+            _ => Some(r.read_u32()?),
+        };
+
+        let checkpoint_count = r.read_u32()?;
+        let mut checkpoints = Vec::with_capacity(checkpoint_count as usize);
+        for _ in 0..checkpoint_count {
+            let ledger_seq = r.read_u32()?;
+            let checkpoint_notes = read_notes(&mut r)?;
+            let checkpoint_nullifiers = rebuild_nullifiers(&checkpoint_notes);
+            let checkpoint_spent_notes = read_spent_notes(&mut r)?;
+            checkpoints.push(WalletCheckpoint {
+                ledger_seq,
+                notes: checkpoint_notes,
+                nullifiers: checkpoint_nullifiers,
+                spent_notes: checkpoint_spent_notes,
+            });
+        }
+
+        validate_note_witnesses(&commitment_tree, &notes)?;
+
+        Ok(Self {
+            ivks,
+            notes,
+            commitment_tree,
+            nullifiers,
+            spent_notes,
+            last_checkpoint,
+            cmx_to_position: BTreeMap::new(),
+            checkpoints,
+        })
+    }
+}
+
+/// Write a note set in the format shared by [`OrchardWalletState::serialize`]'s
+/// top-level `notes` and each retained [`WalletCheckpoint`]'s own note snapshot
+fn write_notes(buf: &mut Vec<u8>, notes: &BTreeMap<NoteId, DecryptedNote>) {
+    buf.extend_from_slice(&(notes.len() as u32).to_le_bytes());
+    for note in notes.values() {
+        buf.extend_from_slice(&note.tx_hash);
+        buf.extend_from_slice(&note.action_idx.to_le_bytes());
+        buf.extend_from_slice(&note.cmx);
+        buf.extend_from_slice(&note.nullifier);
+        buf.extend_from_slice(&note.amount.to_le_bytes());
+        match note.ledger_seq {
+            Some(seq) => {
+                buf.push(1);
+                buf.extend_from_slice(&seq.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        match note.position {
+            Some(position) => {
+                buf.push(1);
+                buf.extend_from_slice(&u64::from(position).to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        buf.extend_from_slice(&note.anchor.to_bytes());
+        buf.extend_from_slice(&(note.ivk_index as u64).to_le_bytes());
+        match note.spent_at {
+            Some((spent_ledger_seq, spent_action_idx)) => {
+                buf.push(1);
+                buf.extend_from_slice(&spent_ledger_seq.to_le_bytes());
+                buf.extend_from_slice(&spent_action_idx.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        buf.push(scope_to_byte(note.scope));
+
+        buf.extend_from_slice(&note.note.recipient().to_raw_address_bytes());
+        buf.extend_from_slice(&note.note.rho().to_bytes());
+        buf.extend_from_slice(note.note.rseed().as_bytes());
+        buf.extend_from_slice(&note.memo);
+        buf.extend_from_slice(&note.asset_id);
+    }
+}
+
+/// Read a note set written by [`write_notes`]
+fn read_notes(r: &mut ByteReader<'_>) -> Result<BTreeMap<NoteId, DecryptedNote>, String> {
+    let mut notes = BTreeMap::new();
+    let note_count = r.read_u32()?;
+    for _ in 0..note_count {
+        let tx_hash = r.read_array::<32>()?;
+        let action_idx = r.read_u32()?;
+        let cmx = r.read_array::<32>()?;
+        let nullifier = r.read_array::<32>()?;
+        let amount = r.read_u64()?;
+        let ledger_seq = match r.read_u8()? {
+            0 => None,
+            _ => Some(r.read_u32()?),
+        };
+        let position = match r.read_u8()? {
+            0 => None,
+            _ => Some(Position::from(r.read_u64()?)),
+        };
+        let anchor_bytes = r.read_array::<32>()?;
+        let anchor = Anchor::from_bytes(anchor_bytes)
+            .into_option()
+            .ok_or_else(|| "Invalid anchor".to_string())?;
+        let ivk_index = r.read_u64()? as usize;
+        let spent_at = match r.read_u8()? {
+            0 => None,
+            _ => Some((r.read_u32()?, r.read_u32()?)),
+        };
+        let scope = scope_from_byte(r.read_u8()?)?;
+
+        let recipient_bytes = r.read_array::<43>()?;
+        let recipient = Address::from_raw_address_bytes(&recipient_bytes)
+            .into_option()
+            .ok_or_else(|| "Invalid note recipient address".to_string())?;
+        let rho_bytes = r.read_array::<32>()?;
+        let rho = Rho::from_bytes(&rho_bytes)
+            .into_option()
+            .ok_or_else(|| "Invalid note rho".to_string())?;
+        let rseed_bytes = r.read_array::<32>()?;
+        let rseed = RandomSeed::from_bytes(rseed_bytes, &rho)
+            .into_option()
+            .ok_or_else(|| "Invalid note random seed".to_string())?;
+        let note = Note::from_parts(recipient, NoteValue::from_raw(amount), rho, rseed)
+            .into_option()
+            .ok_or_else(|| "Invalid note parts".to_string())?;
+        let memo = r.read_array::<512>()?;
+        let asset_id = r.read_array::<32>()?;
+
+        let note_id = (tx_hash, action_idx);
+        notes.insert(note_id, DecryptedNote {
+            note,
+            cmx,
+            nullifier,
+            amount,
+            ledger_seq,
+            tx_hash,
+            action_idx,
+            position,
+            anchor,
+            ivk_index,
+            spent_at,
+            scope,
+            memo,
+            asset_id,
+        });
+    }
+    Ok(notes)
+}
+
+/// Write a spent-note set in the format shared by [`OrchardWalletState::serialize`]'s
+/// top-level `spent_notes` and each retained [`WalletCheckpoint`]'s own snapshot
+fn write_spent_notes(buf: &mut Vec<u8>, spent_notes: &std::collections::HashSet<NoteId>) {
+    buf.extend_from_slice(&(spent_notes.len() as u32).to_le_bytes());
+    for (tx_hash, action_idx) in spent_notes {
+        buf.extend_from_slice(tx_hash);
+        buf.extend_from_slice(&action_idx.to_le_bytes());
+    }
+}
+
+/// Read a spent-note set written by [`write_spent_notes`]
+fn read_spent_notes(r: &mut ByteReader<'_>) -> Result<std::collections::HashSet<NoteId>, String> {
+    let mut spent_notes = std::collections::HashSet::new();
+    let spent_count = r.read_u32()?;
+    for _ in 0..spent_count {
+        let tx_hash = r.read_array::<32>()?;
+        let action_idx = r.read_u32()?;
+        spent_notes.insert((tx_hash, action_idx));
+    }
+    Ok(spent_notes)
+}
+
+/// Rebuild the `nullifier -> note_id` index from a note set, matching how
+/// [`OrchardWalletState::add_note`] maintains it incrementally
+fn rebuild_nullifiers(notes: &BTreeMap<NoteId, DecryptedNote>) -> BTreeMap<[u8; 32], NoteId> {
+    notes.values().map(|note| (note.nullifier, (note.tx_hash, note.action_idx))).collect()
+}
+
+/// Verify that every mined note's witness, generated fresh from the restored
+/// commitment tree, still authenticates to that tree's current root
+///
+/// Called once after deserializing, so a wallet-state blob whose tree and
+/// notes have fallen out of sync (truncated write, bit rot, a hand-edited
+/// file) is rejected up front instead of later producing a spend with an
+/// unverifiable proof.
+fn validate_note_witnesses(
+    tree: &ShardedCommitmentTree<MemoryShardStore>,
+    notes: &BTreeMap<NoteId, DecryptedNote>,
+) -> Result<(), String> {
+    let mined_notes = notes.values().filter(|note| note.position.is_some()).count();
+    if mined_notes == 0 {
+        return Ok(());
+    }
+
+    let root = tree.root(0)
+        .ok_or_else(|| "Corrupted wallet state: tree has mined notes but no root".to_string())?;
+
+    for note in notes.values() {
+        let Some(position) = note.position else { continue };
+
+        let auth_path_vec = tree.witness(position, 0).map_err(|e| {
+            format!("Corrupted wallet state: failed to witness note at position {:?}: {}", position, e)
+        })?;
+
+        let position_u32: u32 = u64::from(position).try_into()
+            .map_err(|_| "Corrupted wallet state: position too large for u32".to_string())?;
+        let mut auth_path = [MerkleHashOrchard::empty_leaf(); 32];
+        for (i, elem) in auth_path_vec.iter().enumerate().take(32) {
+            auth_path[i] = *elem;
+        }
+
+        let path = MerklePath::from_parts(position_u32, auth_path);
+        let cmx = orchard::note::ExtractedNoteCommitment::from(note.note.commitment());
+        if path.root(cmx).to_bytes() != root.to_bytes() {
+            return Err(format!(
+                "Corrupted wallet state: witness for note at position {:?} does not authenticate to the tree root",
+                position,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Encode a [`Scope`] as a single byte for the serialized wallet blob
+fn scope_to_byte(scope: Scope) -> u8 {
+    match scope {
+        Scope::External => 0,
+        Scope::Internal => 1,
+    }
+}
+
+/// Decode a [`Scope`] byte written by [`scope_to_byte`]
+fn scope_from_byte(byte: u8) -> Result<Scope, String> {
+    match byte {
+        0 => Ok(Scope::External),
+        1 => Ok(Scope::Internal),
+        other => Err(format!("Invalid scope byte {}", other)),
+    }
+}
+
+/// Small cursor over a byte slice used by [`OrchardWalletState::deserialize`]
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).ok_or_else(|| "Length overflow".to_string())?;
+        let slice = self.bytes.get(self.pos..end)
+            .ok_or_else(|| "Unexpected end of wallet state blob".to_string())?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        self.read_bytes(N)?.try_into().map_err(|_| "Array length mismatch".to_string())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.read_array::<4>()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.read_array::<8>()?))
     }
 }
 
@@ -566,4 +1464,191 @@ mod tests {
     fn test_ivk_management() {
         // TODO: Add test with actual IVK
     }
+
+    #[test]
+    fn test_serialize_deserialize_round_trip() {
+        use orchard::keys::{Scope, SpendingKey};
+        use orchard::value::NoteValue;
+
+        let mut wallet = OrchardWalletState::new();
+
+        let sk = SpendingKey::from_bytes([1u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let ivk = fvk.to_ivk(Scope::External);
+        let addr = fvk.address_at(0, Scope::External);
+        wallet.add_ivk(ivk, Scope::External);
+
+        let rho = orchard::note::Rho::from_bytes(&[7u8; 32]).unwrap();
+        let rseed = orchard::note::RandomSeed::from_bytes([8u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(4200), rho, rseed).unwrap();
+
+        let cmx = orchard::note::ExtractedNoteCommitment::from(note.commitment()).to_bytes();
+        let nullifier = [10u8; 32];
+        let tx_hash = [11u8; 32];
+
+        wallet.append_commitment(cmx).unwrap();
+        let anchor = wallet.get_anchor().unwrap();
+        wallet.add_note(note, cmx, nullifier, tx_hash, 0, Some(50), anchor, 0, Scope::External, [0u8; 512], NATIVE_ASSET).unwrap();
+        wallet.checkpoint(50);
+
+        let bytes = wallet.serialize();
+        let restored = OrchardWalletState::deserialize(&bytes).unwrap();
+
+        assert_eq!(restored.get_balance(), 4200);
+        assert_eq!(restored.last_checkpoint(), Some(50));
+
+        // The restored auth path must still authenticate to the restored anchor.
+        let restored_note = restored.get_note(&cmx).unwrap();
+        let path = restored.get_merkle_path(restored_note).unwrap();
+        let extracted_cmx = orchard::note::ExtractedNoteCommitment::from(restored_note.note.commitment());
+        assert_eq!(path.root(extracted_cmx), restored.get_anchor().unwrap());
+    }
+
+    #[test]
+    fn test_rewind_undoes_notes_and_spends() {
+        use orchard::keys::{Scope, SpendingKey};
+        use orchard::value::NoteValue;
+
+        let mut wallet = OrchardWalletState::new();
+
+        let sk = SpendingKey::from_bytes([1u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+        wallet.add_ivk(fvk.to_ivk(Scope::External), Scope::External);
+
+        // Ledger 100: receive one note, then checkpoint
+        let rho = orchard::note::Rho::from_bytes(&[1u8; 32]).unwrap();
+        let rseed = orchard::note::RandomSeed::from_bytes([11u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(1000), rho, rseed).unwrap();
+        let cmx = [1u8; 32];
+        let nullifier = [101u8; 32];
+        wallet.append_commitment(cmx).unwrap();
+        let anchor = wallet.get_anchor().unwrap();
+        wallet.add_note(note, cmx, nullifier, [1u8; 32], 0, Some(100), anchor, 0, Scope::External, [0u8; 512], NATIVE_ASSET).unwrap();
+        assert!(wallet.checkpoint(100));
+
+        // Ledger 101: receive a second note and spend the first, then checkpoint
+        let rho2 = orchard::note::Rho::from_bytes(&[2u8; 32]).unwrap();
+        let rseed2 = orchard::note::RandomSeed::from_bytes([12u8; 32], &rho2).unwrap();
+        let note2 = Note::from_parts(addr, NoteValue::from_raw(2000), rho2, rseed2).unwrap();
+        let cmx2 = [2u8; 32];
+        let nullifier2 = [102u8; 32];
+        wallet.append_commitment(cmx2).unwrap();
+        let anchor2 = wallet.get_anchor().unwrap();
+        wallet.add_note(note2, cmx2, nullifier2, [2u8; 32], 0, Some(101), anchor2, 0, Scope::External, [0u8; 512], NATIVE_ASSET).unwrap();
+        wallet.mark_spent(&nullifier);
+        assert!(wallet.checkpoint(101));
+
+        assert_eq!(wallet.get_balance(), 2000);
+
+        // Out-of-order checkpoints are rejected
+        assert!(!wallet.checkpoint(103));
+
+        // Reorg: rewind to ledger 100 should undo both the spend and the second note
+        wallet.rewind(100).unwrap();
+        assert_eq!(wallet.get_balance(), 1000);
+        assert_eq!(wallet.list_notes(false).len(), 1);
+        assert!(wallet.get_note(&cmx).is_some());
+        assert!(wallet.get_note(&cmx2).is_none());
+        assert_eq!(wallet.last_checkpoint(), Some(100));
+
+        // Rewinding to a ledger we never checkpointed is an error
+        assert!(wallet.rewind(999).is_err());
+    }
+
+    #[test]
+    fn test_rewind_to_or_before_finds_nearest_checkpoint() {
+        let mut wallet = OrchardWalletState::new();
+
+        wallet.append_commitment([1u8; 32]).unwrap();
+        assert!(wallet.checkpoint(100));
+        wallet.append_commitment([2u8; 32]).unwrap();
+        assert!(wallet.checkpoint(101));
+        wallet.append_commitment([3u8; 32]).unwrap();
+        assert!(wallet.checkpoint(102));
+
+        // Ledger 105 was never checkpointed (checkpointing lags the tip), so
+        // a reorg targeting it should land on the nearest earlier checkpoint.
+        assert_eq!(wallet.rewind_to_or_before(105).unwrap(), 102);
+        assert_eq!(wallet.last_checkpoint(), Some(102));
+
+        // Predating the oldest retained checkpoint is an error.
+        assert!(wallet.rewind_to_or_before(50).is_err());
+    }
+
+    #[test]
+    fn test_balance_with_confirmations() {
+        use orchard::keys::{Scope, SpendingKey};
+        use orchard::value::NoteValue;
+
+        let mut wallet = OrchardWalletState::new();
+
+        let sk = SpendingKey::from_bytes([3u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+        wallet.add_ivk(fvk.to_ivk(Scope::External), Scope::External);
+
+        // Mined at ledger 100, with a chain tip of 104 -> 5 confirmations
+        let rho = orchard::note::Rho::from_bytes(&[5u8; 32]).unwrap();
+        let rseed = orchard::note::RandomSeed::from_bytes([15u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(1000), rho, rseed).unwrap();
+        let cmx = [5u8; 32];
+        wallet.append_commitment(cmx).unwrap();
+        let anchor = wallet.get_anchor().unwrap();
+        wallet.add_note(note, cmx, [51u8; 32], [5u8; 32], 0, Some(100), anchor, 0, Scope::External, [0u8; 512], NATIVE_ASSET).unwrap();
+
+        // Unmined (mempool) note
+        let rho2 = orchard::note::Rho::from_bytes(&[6u8; 32]).unwrap();
+        let rseed2 = orchard::note::RandomSeed::from_bytes([16u8; 32], &rho2).unwrap();
+        let note2 = Note::from_parts(addr, NoteValue::from_raw(2000), rho2, rseed2).unwrap();
+        wallet.add_note(note2, [6u8; 32], [52u8; 32], [6u8; 32], 0, None, anchor, 0, Scope::External, [0u8; 512], NATIVE_ASSET).unwrap();
+
+        // min_confirmations == 0 includes the mempool note too
+        assert_eq!(wallet.get_balance_with_confirmations(0, 104), 3000);
+        // 5 confirmations are available, so a 5-confirmation threshold includes the mined note
+        assert_eq!(wallet.get_balance_with_confirmations(5, 104), 1000);
+        // but not a 6-confirmation threshold
+        assert_eq!(wallet.get_balance_with_confirmations(6, 104), 0);
+
+        let breakdown = wallet.get_balance_breakdown_with_confirmations(0, 104);
+        assert_eq!(breakdown.get(&NATIVE_ASSET), Some(&3000));
+    }
+
+    #[test]
+    fn test_unmined_note_becomes_spendable_once_mined() {
+        use orchard::keys::{Scope, SpendingKey};
+        use orchard::value::NoteValue;
+
+        let mut wallet = OrchardWalletState::new();
+
+        let sk = SpendingKey::from_bytes([1u8; 32]).unwrap();
+        let fvk = FullViewingKey::from(&sk);
+        let addr = fvk.address_at(0, Scope::External);
+        wallet.add_ivk(fvk.to_ivk(Scope::External), Scope::External);
+
+        let rho = orchard::note::Rho::from_bytes(&[3u8; 32]).unwrap();
+        let rseed = orchard::note::RandomSeed::from_bytes([13u8; 32], &rho).unwrap();
+        let note = Note::from_parts(addr, NoteValue::from_raw(500), rho, rseed).unwrap();
+        let cmx = [3u8; 32];
+        let nullifier = [103u8; 32];
+        let tx_hash = [3u8; 32];
+
+        // Seen in the mempool: no tree position yet, so no commitment is appended.
+        let anchor = Anchor::from_bytes([0u8; 32]).into_option().unwrap();
+        wallet.add_note(note, cmx, nullifier, tx_hash, 0, None, anchor, 0, Scope::External, [0u8; 512], NATIVE_ASSET).unwrap();
+
+        assert_eq!(wallet.get_balance(), 0);
+        assert_eq!(wallet.get_balance_with_pending(true), 500);
+        assert_eq!(wallet.get_spendable_notes().len(), 0);
+        assert!(wallet.get_merkle_path(wallet.get_note(&cmx).unwrap()).is_err());
+
+        // Mined: the commitment is appended, then the note is backfilled with its position.
+        wallet.append_commitment(cmx).unwrap();
+        wallet.mark_note_mined(&cmx, 200).unwrap();
+
+        assert_eq!(wallet.get_balance(), 500);
+        assert_eq!(wallet.get_spendable_notes().len(), 1);
+        assert_eq!(wallet.get_note(&cmx).unwrap().ledger_seq, Some(200));
+        assert!(wallet.get_merkle_path(wallet.get_note(&cmx).unwrap()).is_ok());
+    }
 }